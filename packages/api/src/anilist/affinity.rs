@@ -0,0 +1,279 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde::Deserialize;
+use serde_json::json;
+use actix_web::{web, post, HttpResponse, Responder};
+use colourful_logger::Logger;
+use lazy_static::lazy_static;
+use crate::anilist::enums::{known_value, MEDIA_TYPES};
+use crate::anilist::lists::{fetch_media_list, list_cached, list_key, listed_ids};
+use crate::global::rate_budget::{budget_delay, wait_for_budget};
+use crate::global::statistics::{mean, pearson};
+
+lazy_static! {
+    static ref logger: Logger = Logger::default();
+    /// Lists queued to be fetched in the background, so polling a leaderboard does not queue the same users again.
+    static ref queued_lists: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+const HIGHLIGHTS: usize = 5;
+const MAX_GROUP_SIZE: usize = 50;
+const MAX_WAIT: Duration = Duration::from_secs(15);
+
+#[derive(Deserialize)]
+struct AffinityRequest {
+    user_one:   String,
+    user_two:   String,
+    media_type: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LeaderboardRequest {
+    target:     String,
+    usernames:  Vec<String>,
+    media_type: Option<String>,
+}
+
+#[post("/user/affinity")]
+pub async fn user_affinity(req: web::Json<AffinityRequest>) -> impl Responder {
+    let Some(media_type) = known_value(req.media_type.as_deref().unwrap_or("ANIME"), &MEDIA_TYPES) else {
+        logger.error_single(&format!("Unknown media type : {:?}", req.media_type), "Affinity");
+        return HttpResponse::BadRequest().json(json!({"error": "media_type must be ANIME or MANGA"}));
+    };
+
+    if req.user_one.trim().is_empty() || req.user_two.trim().is_empty() {
+        logger.error_single("Two usernames were not included", "Affinity");
+        return HttpResponse::BadRequest().json(json!({"error": "Both user_one and user_two must be included"}));
+    }
+
+    let first = match fetch_media_list(req.user_one.trim(), media_type).await {
+        Ok(list) => list,
+        Err(response) => return response,
+    };
+    let second = match fetch_media_list(req.user_two.trim(), media_type).await {
+        Ok(list) => list,
+        Err(response) => return response,
+    };
+
+    let mut affinity = compare_lists(&first, &second);
+    affinity["users"] = json!([first["user"], second["user"]]);
+    affinity["mediaType"] = media_type.into();
+
+    HttpResponse::Ok().json(affinity)
+}
+
+#[post("/user/affinity/leaderboard")]
+pub async fn affinity_leaderboard(req: web::Json<LeaderboardRequest>) -> impl Responder {
+    let Some(media_type) = known_value(req.media_type.as_deref().unwrap_or("ANIME"), &MEDIA_TYPES) else {
+        logger.error_single(&format!("Unknown media type : {:?}", req.media_type), "Affinity");
+        return HttpResponse::BadRequest().json(json!({"error": "media_type must be ANIME or MANGA"}));
+    };
+
+    let target = req.target.trim();
+    let mut seen: HashSet<String> = HashSet::from([target.to_lowercase()]);
+    let usernames: Vec<&str> = req.usernames
+        .iter()
+        .map(|username| username.trim())
+        .filter(|username| !username.is_empty() && seen.insert(username.to_lowercase()))
+        .collect();
+
+    if target.is_empty() || usernames.is_empty() || usernames.len() > MAX_GROUP_SIZE {
+        logger.error_single(&format!("Leaderboard of {} users was requested", usernames.len()), "Affinity");
+        let bad_json = json!({"error": format!("A target and between 1 and {} other usernames must be included", MAX_GROUP_SIZE)});
+        return HttpResponse::BadRequest().json(bad_json);
+    }
+
+    let target_list = match fetch_media_list(target, media_type).await {
+        Ok(list) => list,
+        Err(response) => return response,
+    };
+
+    // Only lists missing from the cache cost a request, and only those that fit within MAX_WAIT are fetched now.
+    // The rest are fetched in the background and returned as pending, so asking again later completes the leaderboard
+    let deadline = Instant::now() + MAX_WAIT;
+    let mut leaderboard: Vec<serde_json::Value> = Vec::new();
+    let mut failed: Vec<serde_json::Value> = Vec::new();
+    let mut pending: Vec<String> = Vec::new();
+
+    for username in usernames {
+        if !list_cached(username, media_type) {
+            if Instant::now() + budget_delay() > deadline {
+                pending.push(username.to_string());
+                continue;
+            }
+            wait_for_budget().await;
+        }
+
+        match fetch_media_list(username, media_type).await {
+            Ok(list) => {
+                let comparison = compare_lists(&target_list, &list);
+                leaderboard.push(json!({
+                    "user"          : list["user"],
+                    "affinity"      : comparison["affinity"],
+                    "sharedCount"   : comparison["sharedCount"],
+                    "sharedListed"  : comparison["sharedListed"],
+                }));
+            },
+            Err(response) => {
+                logger.warn_single(&format!("Unable to fetch the list of {}, leaving them out", username), "Affinity");
+                failed.push(json!({"username": username, "errorCode": response.status().as_u16()}));
+            }
+        }
+    }
+
+    // Users without an affinity (too few shared scores) are ranked last
+    leaderboard.sort_by(|a, b| {
+        let affinity = |entry: &serde_json::Value| entry["affinity"].as_f64();
+        match (affinity(a), affinity(b)) {
+            (Some(a_affinity), Some(b_affinity)) => b_affinity.total_cmp(&a_affinity),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+        .then(b["sharedCount"].as_u64().cmp(&a["sharedCount"].as_u64()))
+    });
+
+    for (rank, entry) in leaderboard.iter_mut().enumerate() {
+        entry["rank"] = (rank + 1).into();
+    }
+
+    let to_queue: Vec<String> = {
+        let mut queued = queued_lists.lock().unwrap();
+        pending.iter().filter(|username| queued.insert(list_key(username, media_type))).cloned().collect()
+    };
+
+    if !to_queue.is_empty() {
+        logger.info_single(&format!("Fetching {} lists for a leaderboard in the background", to_queue.len()), "Affinity");
+        tokio::spawn(fetch_pending(to_queue, media_type));
+    }
+
+    HttpResponse::Ok().json(json!({
+        "target"        : target_list["user"],
+        "mediaType"     : media_type,
+        "leaderboard"   : leaderboard,
+        "failed"        : failed,
+        "pending"       : pending,
+    }))
+}
+
+async fn fetch_pending(usernames: Vec<String>, media_type: &'static str) {
+    for username in usernames {
+        if !list_cached(&username, media_type) {
+            wait_for_budget().await;
+            let _ = fetch_media_list(&username, media_type).await;
+        }
+        queued_lists.lock().unwrap().remove(&list_key(&username, media_type));
+    }
+}
+
+fn scored_entries(list: &serde_json::Value) -> HashMap<i64, (f64, serde_json::Value)> {
+    list["entries"]
+        .as_array()
+        .unwrap_or(&vec![])
+        .iter()
+        .filter_map(|entry| {
+            let score = entry["score"].as_f64().filter(|score| *score > 0.0)?;
+            Some((entry["mediaId"].as_i64()?, (score, entry["title"].clone())))
+        })
+        .collect()
+}
+
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Compares the scores two users gave the media they both scored, scores being on the 100 point scale.
+fn compare_lists(first: &serde_json::Value, second: &serde_json::Value) -> serde_json::Value {
+    let first_scores = scored_entries(first);
+    let second_scores = scored_entries(second);
+    let shared_listed = listed_ids(first).intersection(&listed_ids(second)).count();
+
+    let mut shared: Vec<(i64, serde_json::Value, f64, f64)> = first_scores
+        .iter()
+        .filter_map(|(media_id, (score, title))| {
+            second_scores.get(media_id).map(|(other_score, _)| (*media_id, title.clone(), *score, *other_score))
+        })
+        .collect();
+    shared.sort_by_key(|(media_id, _, _, _)| *media_id);
+
+    let pairs: Vec<(f64, f64)> = shared.iter().map(|(_, _, score, other_score)| (*score, *other_score)).collect();
+    let correlation = pearson(&pairs);
+
+    let highlight = |(media_id, title, score, other_score): &(i64, serde_json::Value, f64, f64)| json!({
+        "mediaId"       : media_id,
+        "title"         : title,
+        "scores"        : [score, other_score],
+        "difference"    : (score - other_score).abs(),
+    });
+
+    // Agreements favour media both rated highly, so two matching low scores rank below two matching high ones
+    let mut agreements = shared.clone();
+    agreements.sort_by(|a, b| (a.2 - a.3).abs().total_cmp(&(b.2 - b.3).abs()).then((b.2 + b.3).total_cmp(&(a.2 + a.3))));
+    let mut disagreements = shared.clone();
+    disagreements.sort_by(|a, b| (b.2 - b.3).abs().total_cmp(&(a.2 - a.3).abs()));
+
+    json!({
+        "affinity"      : correlation.map(|correlation| round(correlation * 100.0)),
+        "correlation"   : correlation.map(|correlation| (correlation * 10000.0).round() / 10000.0),
+        "sharedCount"   : shared.len(),
+        "sharedListed"  : shared_listed,
+        "meanScores"    : [
+            mean(&pairs.iter().map(|(score, _)| *score).collect::<Vec<f64>>()).map(round),
+            mean(&pairs.iter().map(|(_, score)| *score).collect::<Vec<f64>>()).map(round),
+        ],
+        "agreements"    : agreements.iter().take(HIGHLIGHTS).map(highlight).collect::<Vec<serde_json::Value>>(),
+        "disagreements" : disagreements.iter().take(HIGHLIGHTS).map(highlight).collect::<Vec<serde_json::Value>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(scores: &[(i64, f64)]) -> serde_json::Value {
+        let entries: Vec<serde_json::Value> = scores
+            .iter()
+            .map(|(media_id, score)| json!({"mediaId": media_id, "title": format!("Media {}", media_id), "score": score}))
+            .collect();
+        json!({"entries": entries})
+    }
+
+    #[test]
+    fn too_few_shared_scores() {
+        let comparison = compare_lists(&list(&[(1, 80.0), (2, 70.0)]), &list(&[(1, 60.0), (3, 90.0)]));
+        assert!(comparison["affinity"].is_null());
+        assert_eq!(comparison["sharedCount"], 1);
+        assert_eq!(comparison["sharedListed"], 1);
+    }
+
+    #[test]
+    fn unscored_entries_are_only_listed() {
+        let comparison = compare_lists(&list(&[(1, 80.0), (2, 0.0)]), &list(&[(1, 60.0), (2, 90.0)]));
+        assert_eq!(comparison["sharedCount"], 1);
+        assert_eq!(comparison["sharedListed"], 2);
+    }
+
+    #[test]
+    fn same_score_everywhere() {
+        let comparison = compare_lists(&list(&[(1, 80.0), (2, 80.0), (3, 80.0)]), &list(&[(1, 20.0), (2, 50.0), (3, 90.0)]));
+        assert!(comparison["affinity"].is_null());
+        assert!(comparison["correlation"].is_null());
+        assert_eq!(comparison["sharedCount"], 3);
+    }
+
+    #[test]
+    fn known_affinity() {
+        let first = list(&[(1, 10.0), (2, 20.0), (3, 30.0), (4, 40.0), (5, 50.0)]);
+        let second = list(&[(1, 20.0), (2, 40.0), (3, 50.0), (4, 40.0), (5, 50.0), (6, 100.0)]);
+        let comparison = compare_lists(&first, &second);
+
+        assert_eq!(comparison["affinity"], 77.46);
+        assert_eq!(comparison["correlation"], 0.7746);
+        assert_eq!(comparison["meanScores"], json!([30.0, 40.0]));
+        assert_eq!(comparison["agreements"][0]["mediaId"], 5);
+        assert_eq!(comparison["agreements"][1]["mediaId"], 4);
+        assert_eq!(comparison["disagreements"][0]["mediaId"], 2);
+    }
+}
@@ -0,0 +1,139 @@
+use serde::Deserialize;
+use serde_json::json;
+use actix_web::{web, get, HttpResponse, Responder};
+use colourful_logger::Logger;
+use lazy_static::lazy_static;
+use crate::anilist::client::send_query;
+use crate::anilist::media::media_ttl;
+use crate::cache::backend::{cached_json, store_json};
+use crate::cache::keys::media_key;
+
+lazy_static! {
+    static ref logger: Logger = Logger::default();
+}
+
+const LANGUAGES: [&str; 10] = ["JAPANESE", "ENGLISH", "KOREAN", "ITALIAN", "SPANISH", "PORTUGUESE", "FRENCH", "GERMAN", "HEBREW", "HUNGARIAN"];
+
+#[derive(Deserialize)]
+struct PeopleQuery {
+    page:       Option<i64>,
+    per_page:   Option<i64>,
+    language:   Option<String>,
+}
+
+#[get("/media/{id}/characters")]
+pub async fn media_characters(path: web::Path<i64>, query: web::Query<PeopleQuery>) -> impl Responder {
+    let media_id = path.into_inner();
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(25).clamp(1, 50);
+    let language = query.language.as_ref().map(|language| language.to_uppercase());
+
+    if let Some(language) = &language {
+        if !LANGUAGES.contains(&language.as_str()) {
+            logger.error_single(&format!("Unknown voice actor language : {}", language), "Characters");
+            return HttpResponse::BadRequest().json(json!({"error": "Unknown language", "languages": LANGUAGES}));
+        }
+    }
+
+    let redis_key = format!("{}:characters:{}:{}:{}", media_key(media_id), page, per_page, language.as_deref().unwrap_or("all").to_lowercase());
+    if let Some(characters) = cached_json(&redis_key) {
+        logger.debug_single(&format!("Found characters for {} in cache", media_id), "Characters");
+        return HttpResponse::Ok().json(characters);
+    }
+
+    let variables = json!({"id": media_id, "page": page, "perPage": per_page, "language": language});
+    let characters = match send_query("characters", variables, "Characters").await {
+        Ok(characters) => wash_characters(characters),
+        Err(response) => return response,
+    };
+
+    store_json(&redis_key, &characters, media_ttl(media_id));
+    HttpResponse::Ok().json(characters)
+}
+
+#[get("/media/{id}/staff")]
+pub async fn media_staff(path: web::Path<i64>, query: web::Query<PeopleQuery>) -> impl Responder {
+    let media_id = path.into_inner();
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(25).clamp(1, 50);
+
+    let redis_key = format!("{}:staff:{}:{}", media_key(media_id), page, per_page);
+    if let Some(staff) = cached_json(&redis_key) {
+        logger.debug_single(&format!("Found staff for {} in cache", media_id), "Staff");
+        return HttpResponse::Ok().json(staff);
+    }
+
+    let variables = json!({"id": media_id, "page": page, "perPage": per_page});
+    let staff = match send_query("staff", variables, "Staff").await {
+        Ok(staff) => wash_staff(staff),
+        Err(response) => return response,
+    };
+
+    store_json(&redis_key, &staff, media_ttl(media_id));
+    HttpResponse::Ok().json(staff)
+}
+
+fn wash_person(person: &serde_json::Value) -> serde_json::Value {
+    json!({
+        "id"        : person["id"],
+        "name"      : person["name"]["full"],
+        "native"    : person["name"]["native"],
+        "image"     : person["image"]["large"],
+        "url"       : person["siteUrl"],
+    })
+}
+
+fn wash_characters(characters: serde_json::Value) -> serde_json::Value {
+    logger.debug_single("Washing up character data", "Characters");
+    let data = &characters["data"]["Media"]["characters"];
+    let mut washed_characters = Vec::new();
+
+    for edge in data["edges"].as_array().unwrap_or(&vec![]) {
+        let mut voice_actors = serde_json::Map::new();
+        for actor in edge["voiceActors"].as_array().unwrap_or(&vec![]) {
+            let language = actor["languageV2"].as_str().unwrap_or("Unknown").to_string();
+            let actors = voice_actors.entry(language).or_insert_with(|| json!([]));
+            actors.as_array_mut().unwrap().push(wash_person(actor));
+        }
+
+        let mut character = wash_person(&edge["node"]);
+        character["role"] = edge["role"].clone();
+        character["favourites"] = edge["node"]["favourites"].clone();
+        character["voiceActors"] = voice_actors.into();
+        washed_characters.push(character);
+    }
+
+    json!({
+        "mediaId"       : characters["data"]["Media"]["id"],
+        "pageInfo"      : data["pageInfo"],
+        "characters"    : washed_characters,
+        "dataFrom"      : "API",
+    })
+}
+
+fn wash_staff(staff: serde_json::Value) -> serde_json::Value {
+    logger.debug_single("Washing up staff data", "Staff");
+    let data = &staff["data"]["Media"]["staff"];
+    let mut washed_staff: Vec<serde_json::Value> = Vec::new();
+
+    // The same person is returned once for every role they had, so roles are merged per person
+    for edge in data["edges"].as_array().unwrap_or(&vec![]) {
+        if let Some(member) = washed_staff.iter_mut().find(|member| member["id"] == edge["node"]["id"]) {
+            member["roles"].as_array_mut().unwrap().push(edge["role"].clone());
+            continue;
+        }
+
+        let mut member = wash_person(&edge["node"]);
+        member["language"] = edge["node"]["languageV2"].clone();
+        member["occupations"] = edge["node"]["primaryOccupations"].clone();
+        member["roles"] = json!([edge["role"]]);
+        washed_staff.push(member);
+    }
+
+    json!({
+        "mediaId"       : staff["data"]["Media"]["id"],
+        "pageInfo"      : data["pageInfo"],
+        "staff"         : washed_staff,
+        "dataFrom"      : "API",
+    })
+}
@@ -0,0 +1,62 @@
+use reqwest::{Client, Response};
+use serde_json::json;
+use actix_web::HttpResponse;
+use colourful_logger::Logger;
+use lazy_static::lazy_static;
+use crate::anilist::queries::{get_query, QUERY_URL};
+use crate::cache::proxy::{get_random_proxy, remove_proxy};
+
+lazy_static! {
+    static ref logger: Logger = Logger::default();
+}
+
+/// Sends a named query through a random proxy, returning the response body or the error response for the caller to return.
+pub async fn send_query(query_name: &str, variables: serde_json::Value, section: &str) -> Result<serde_json::Value, HttpResponse> {
+    let get_proxy = match get_random_proxy().await {
+        Ok(proxy) => proxy,
+        Err(e) => {
+            logger.error_single(&format!("Unable to get a proxy: {:?}", e), section);
+            let bad_json = json!({"error": "No proxies available"});
+            return Err(HttpResponse::ServiceUnavailable().json(bad_json));
+        }
+    };
+
+    let proxy = reqwest::Proxy::http(get_proxy.clone()).unwrap();
+    let client = Client::builder().proxy(proxy).build().unwrap();
+    let json = json!({"query": get_query(query_name), "variables": variables});
+    logger.debug_single(&format!("Sending {} query", query_name), section);
+
+    let response: Response = match client.post(QUERY_URL).json(&json).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            logger.error_single(&format!("Request failed when sending {} query: {:?}", query_name, e), section);
+            let bad_json = json!({"error": "Request failed"});
+            return Err(HttpResponse::BadGateway().json(bad_json));
+        }
+    };
+
+    if response.status().as_u16() != 200 {
+        if response.status().as_u16() == 403 {
+            let _ = remove_proxy(get_proxy.as_str()).await;
+        }
+
+        if response.status().as_u16() == 404 {
+            logger.warn_single(&format!("{} query returned not found", query_name), section);
+            let not_found = json!({"error": "Not found", "errorCode": 404, "dataFrom": "API"});
+            return Err(HttpResponse::NotFound().json(not_found));
+        }
+
+        logger.error_single(&format!("Request returned {} when sending {} query", response.status().as_str(), query_name), section);
+        let bad_json = json!({"error": "Request returned an error", "errorCode": response.status().as_u16()});
+        return Err(HttpResponse::BadRequest().json(bad_json));
+    }
+
+    match response.json::<serde_json::Value>().await {
+        Ok(data) => Ok(data),
+        Err(e) => {
+            logger.error_single(&format!("Unable to parse {} response: {:?}", query_name, e), section);
+            let bad_json = json!({"error": "Invalid response from Anilist"});
+            Err(HttpResponse::BadGateway().json(bad_json))
+        }
+    }
+}
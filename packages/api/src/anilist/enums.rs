@@ -0,0 +1,59 @@
+pub const MEDIA_TYPES: [&str; 2] = ["ANIME", "MANGA"];
+pub const MEDIA_FORMATS: [&str; 10] = ["TV", "TV_SHORT", "MOVIE", "SPECIAL", "OVA", "ONA", "MUSIC", "MANGA", "NOVEL", "ONE_SHOT"];
+pub const MEDIA_STATUSES: [&str; 5] = ["FINISHED", "RELEASING", "NOT_YET_RELEASED", "CANCELLED", "HIATUS"];
+pub const COUNTRIES: [&str; 4] = ["JP", "KR", "CN", "TW"];
+
+pub const GENRES: [&str; 19] = [
+    "Action", "Adventure", "Comedy", "Drama", "Ecchi", "Fantasy", "Hentai", "Horror", "Mahou Shoujo", "Mecha",
+    "Music", "Mystery", "Psychological", "Romance", "Sci-Fi", "Slice of Life", "Sports", "Supernatural", "Thriller",
+];
+
+pub const MEDIA_SORTS: [&str; 20] = [
+    "POPULARITY_DESC", "POPULARITY", "SCORE_DESC", "SCORE", "TRENDING_DESC", "TRENDING", "FAVOURITES_DESC", "FAVOURITES",
+    "START_DATE_DESC", "START_DATE", "END_DATE_DESC", "END_DATE", "TITLE_ROMAJI", "TITLE_ROMAJI_DESC", "TITLE_ENGLISH",
+    "TITLE_ENGLISH_DESC", "EPISODES_DESC", "EPISODES", "SEARCH_MATCH", "ID_DESC",
+];
+
+pub const STATUS_LABELS: [(&str, &str); 5] = [
+    ("FINISHED", "Finished"), ("RELEASING", "Releasing"), ("NOT_YET_RELEASED", "Not Yet Released"), ("CANCELLED", "Cancelled"), ("HIATUS", "Hiatus"),
+];
+
+pub const FORMAT_LABELS: [(&str, &str); 10] = [
+    ("TV", "TV"), ("TV_SHORT", "TV Short"), ("MOVIE", "Movie"), ("SPECIAL", "Special"), ("OVA", "OVA"),
+    ("ONA", "ONA"), ("MUSIC", "Music"), ("MANGA", "Manga"), ("NOVEL", "Light Novel"), ("ONE_SHOT", "One Shot"),
+];
+
+pub const SEASON_LABELS: [(&str, &str); 4] = [("WINTER", "Winter"), ("SPRING", "Spring"), ("SUMMER", "Summer"), ("FALL", "Fall")];
+
+pub const SOURCE_LABELS: [(&str, &str); 15] = [
+    ("ORIGINAL", "Original"), ("MANGA", "Manga"), ("LIGHT_NOVEL", "Light Novel"), ("VISUAL_NOVEL", "Visual Novel"),
+    ("VIDEO_GAME", "Video Game"), ("OTHER", "Other"), ("NOVEL", "Novel"), ("DOUJINSHI", "Doujinshi"), ("ANIME", "Anime"),
+    ("WEB_NOVEL", "Web Novel"), ("LIVE_ACTION", "Live Action"), ("GAME", "Game"), ("COMIC", "Comic"),
+    ("MULTIMEDIA_PROJECT", "Multimedia Project"), ("PICTURE_BOOK", "Picture Book"),
+];
+
+/// Pairs an Anilist enum value with its label, values Anilist added after these tables are labelled from their raw value.
+pub fn labelled(value: &serde_json::Value, labels: &[(&str, &str)]) -> serde_json::Value {
+    let Some(raw) = value.as_str() else {
+        return serde_json::Value::Null;
+    };
+
+    let label = match labels.iter().find(|(known, _)| *known == raw) {
+        Some((_, label)) => label.to_string(),
+        None => raw
+            .split('_')
+            .map(|word| {
+                let mut letters = word.chars();
+                letters.next().map(|first| first.to_string() + &letters.as_str().to_lowercase()).unwrap_or_default()
+            })
+            .collect::<Vec<String>>()
+            .join(" "),
+    };
+
+    serde_json::json!({"raw": raw, "label": label})
+}
+
+/// Matches a value against a list of known values regardless of case, returning it as Anilist expects it.
+pub fn known_value(value: &str, known: &[&'static str]) -> Option<&'static str> {
+    known.iter().find(|known| known.eq_ignore_ascii_case(value.trim())).copied()
+}
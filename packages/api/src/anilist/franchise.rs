@@ -0,0 +1,266 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+use serde::Deserialize;
+use serde_json::json;
+use actix_web::{web, get, HttpResponse, Responder};
+use colourful_logger::Logger;
+use lazy_static::lazy_static;
+use crate::anilist::client::send_query;
+use crate::cache::backend::{cache, cached_json, store_json};
+use crate::cache::keys::media_key;
+use crate::global::rate_budget::{budget_delay, wait_for_budget};
+use crate::global::season::sortable_date;
+
+lazy_static! {
+    static ref logger: Logger = Logger::default();
+}
+
+const FRANCHISE_RELATIONS: [&str; 7] = ["PREQUEL", "SEQUEL", "PARENT", "SIDE_STORY", "SPIN_OFF", "ALTERNATIVE", "SUMMARY"];
+const MAX_DEPTH: i64 = 5;
+const MAX_NODES: usize = 50;
+const MAX_WAIT: Duration = Duration::from_secs(15);
+
+#[derive(Deserialize)]
+struct FranchiseQuery {
+    depth:          Option<i64>,
+    include_other:  Option<bool>,
+}
+
+#[get("/media/{id}/franchise")]
+pub async fn media_franchise(path: web::Path<i64>, query: web::Query<FranchiseQuery>) -> impl Responder {
+    let root_id = path.into_inner();
+    let max_depth = query.depth.unwrap_or(3).clamp(1, MAX_DEPTH);
+    let include_other = query.include_other.unwrap_or(false);
+
+    let root = match fetch_node(root_id).await {
+        Ok(root) => root,
+        Err(response) => return response,
+    };
+    let root_type = root["type"].clone();
+
+    let mut nodes: HashMap<i64, serde_json::Value> = HashMap::new();
+    let mut edges: Vec<serde_json::Value> = Vec::new();
+    let mut visited: HashSet<i64> = HashSet::from([root_id]);
+    let mut queue: VecDeque<(i64, i64)> = VecDeque::from([(root_id, 0)]);
+    let mut truncated = false;
+    nodes.insert(root_id, root);
+
+    // Uncached nodes wait for the shared rate budget, those that would not be fetched within MAX_WAIT are left out
+    let deadline = Instant::now() + MAX_WAIT;
+
+    while let Some((media_id, depth)) = queue.pop_front() {
+        let node = match nodes.get(&media_id) {
+            Some(node) => node.clone(),
+            None if !node_cached(media_id) && Instant::now() + budget_delay() > deadline => {
+                truncated = true;
+                continue;
+            },
+            None => match fetch_node(media_id).await {
+                Ok(node) => {
+                    nodes.insert(media_id, node.clone());
+                    node
+                },
+                Err(_) => {
+                    logger.warn_single(&format!("Skipping franchise node {}, unable to fetch it", media_id), "Franchise");
+                    continue;
+                }
+            },
+        };
+        nodes.get_mut(&media_id).unwrap()["depth"] = depth.into();
+
+        for relation in node["relations"].as_array().unwrap_or(&vec![]) {
+            let relation_type = relation["relationType"].as_str().unwrap_or("");
+            let Some(related_id) = relation["id"].as_i64() else {
+                continue;
+            };
+
+            if !FRANCHISE_RELATIONS.contains(&relation_type) || (!include_other && relation["type"] != root_type) {
+                continue;
+            }
+
+            edges.push(json!({"from": media_id, "to": related_id, "relation": relation_type}));
+            if depth + 1 > max_depth || visited.contains(&related_id) {
+                continue;
+            }
+
+            if visited.len() >= MAX_NODES {
+                truncated = true;
+                continue;
+            }
+
+            visited.insert(related_id);
+            queue.push_back((related_id, depth + 1));
+        }
+    }
+
+    // Edges to media past the depth or node limit are dropped, so the graph only references returned nodes
+    edges.retain(|edge| nodes.contains_key(&edge["to"].as_i64().unwrap()));
+
+    let mut release_order: Vec<&serde_json::Value> = nodes.values().collect();
+    release_order.sort_by(|a, b| compare_release(a, b));
+    let release_order: Vec<i64> = release_order.iter().map(|node| node["id"].as_i64().unwrap()).collect();
+    let watch_order = watch_order(&nodes, &edges, &release_order);
+
+    let mut graph_nodes: Vec<serde_json::Value> = nodes.into_values().collect();
+    for node in graph_nodes.iter_mut() {
+        node.as_object_mut().unwrap().remove("relations");
+    }
+    graph_nodes.sort_by(compare_release);
+
+    HttpResponse::Ok().json(json!({
+        "rootId"        : root_id,
+        "depth"         : max_depth,
+        "truncated"     : truncated,
+        "nodes"         : graph_nodes,
+        "edges"         : edges,
+        "releaseOrder"  : release_order,
+        "watchOrder"    : watch_order,
+    }))
+}
+
+fn compare_release(a: &serde_json::Value, b: &serde_json::Value) -> Ordering {
+    let date = |node: &serde_json::Value| node["startDate"].as_i64().unwrap_or(i64::MAX);
+    date(a).cmp(&date(b)).then(a["id"].as_i64().cmp(&b["id"].as_i64()))
+}
+
+/// Orders the franchise by story chronology, prequels and parents before what follows them, falling back to release order.
+fn watch_order(nodes: &HashMap<i64, serde_json::Value>, edges: &[serde_json::Value], release_order: &[i64]) -> Vec<i64> {
+    let mut before: HashMap<i64, HashSet<i64>> = nodes.keys().map(|id| (*id, HashSet::new())).collect();
+
+    for edge in edges {
+        let (from, to) = (edge["from"].as_i64().unwrap(), edge["to"].as_i64().unwrap());
+        let (earlier, later) = match edge["relation"].as_str().unwrap_or("") {
+            "PREQUEL" | "PARENT" => (to, from),
+            "SEQUEL" | "SIDE_STORY" | "SPIN_OFF" => (from, to),
+            _ => continue,
+        };
+        if earlier != later {
+            before.entry(later).or_default().insert(earlier);
+        }
+    }
+
+    let mut order: Vec<i64> = Vec::new();
+    let mut placed: HashSet<i64> = HashSet::new();
+
+    while order.len() < release_order.len() {
+        // Earliest released entry with nothing left before it, or the cycle is broken when every remaining entry waits on another
+        let next = release_order
+            .iter()
+            .find(|id| !placed.contains(*id) && before[*id].iter().all(|earlier| placed.contains(earlier)))
+            .copied()
+            .unwrap_or_else(|| cycle_start(&before, &placed, release_order));
+
+        placed.insert(next);
+        order.push(next);
+    }
+
+    order
+}
+
+fn relations_key(media_id: i64) -> String {
+    format!("{}:relations", media_key(media_id))
+}
+
+fn node_cached(media_id: i64) -> bool {
+    cache.get(&relations_key(media_id)).is_ok()
+}
+
+/// Finds a cycle among the remaining entries and returns its earliest released entry.
+fn cycle_start(before: &HashMap<i64, HashSet<i64>>, placed: &HashSet<i64>, release_order: &[i64]) -> i64 {
+    let position = |id: &i64| release_order.iter().position(|other| other == id);
+    let mut current = *release_order.iter().find(|id| !placed.contains(*id)).unwrap();
+    let mut path: Vec<i64> = Vec::new();
+
+    // Every remaining entry waits on another remaining one, so walking back through them has to come round again
+    while !path.contains(&current) {
+        path.push(current);
+        current = *before[&current].iter().filter(|earlier| !placed.contains(*earlier)).min_by_key(|earlier| position(earlier)).unwrap();
+    }
+
+    let cycle = &path[path.iter().position(|id| *id == current).unwrap()..];
+    *cycle.iter().min_by_key(|id| position(id)).unwrap()
+}
+
+/// Fetches a single media with its relation edges, cached for a week as relations rarely change.
+async fn fetch_node(media_id: i64) -> Result<serde_json::Value, HttpResponse> {
+    let redis_key = relations_key(media_id);
+    if let Some(mut node) = cached_json(&redis_key) {
+        logger.debug_single(&format!("Found relations for {} in cache", media_id), "Franchise");
+        let object = node.as_object_mut().unwrap();
+        object.remove("dataFrom");
+        object.remove("leftUntilExpire");
+        return Ok(node);
+    }
+
+    wait_for_budget().await;
+    let media = send_query("media_relations", json!({"id": media_id}), "Franchise").await?;
+    let node = wash_node(&media["data"]["Media"]);
+    store_json(&redis_key, &node, 604800);
+    Ok(node)
+}
+
+fn wash_node(media: &serde_json::Value) -> serde_json::Value {
+    let relations: Vec<serde_json::Value> = media["relations"]["edges"]
+        .as_array()
+        .unwrap_or(&vec![])
+        .iter()
+        .map(|edge| json!({
+            "id"            : edge["node"]["id"],
+            "type"          : edge["node"]["type"],
+            "relationType"  : edge["relationType"],
+        }))
+        .collect();
+
+    json!({
+        "id"        : media["id"],
+        "romaji"    : media["title"]["romaji"],
+        "english"   : media["title"]["english"],
+        "type"      : media["type"],
+        "format"    : media["format"],
+        "status"    : media["status"],
+        "episodes"  : media["episodes"],
+        "chapters"  : media["chapters"],
+        "cover"     : media["coverImage"]["large"],
+        "url"       : media["siteUrl"],
+        "startDate" : sortable_date(&media["startDate"]),
+        "relations" : relations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nodes(ids: &[i64]) -> HashMap<i64, serde_json::Value> {
+        ids.iter().map(|id| (*id, json!({"id": id}))).collect()
+    }
+
+    fn edge(from: i64, to: i64, relation: &str) -> serde_json::Value {
+        json!({"from": from, "to": to, "relation": relation})
+    }
+
+    #[test]
+    fn prequels_come_first() {
+        let edges = [edge(1, 2, "PREQUEL"), edge(2, 1, "SEQUEL")];
+        assert_eq!(watch_order(&nodes(&[1, 2]), &edges, &[1, 2]), vec![2, 1]);
+    }
+
+    #[test]
+    fn parents_come_before_side_stories() {
+        let edges = [edge(3, 1, "PARENT"), edge(1, 4, "SIDE_STORY"), edge(1, 5, "SPIN_OFF")];
+        assert_eq!(watch_order(&nodes(&[1, 3, 4, 5]), &edges, &[5, 4, 3, 1]), vec![1, 5, 4, 3]);
+    }
+
+    #[test]
+    fn other_relations_keep_release_order() {
+        let edges = [edge(1, 2, "ALTERNATIVE"), edge(2, 1, "SUMMARY")];
+        assert_eq!(watch_order(&nodes(&[1, 2]), &edges, &[2, 1]), vec![2, 1]);
+    }
+
+    #[test]
+    fn cycles_fall_back_to_release_order() {
+        let edges = [edge(1, 2, "SEQUEL"), edge(2, 1, "SEQUEL"), edge(2, 3, "SEQUEL")];
+        assert_eq!(watch_order(&nodes(&[1, 2, 3]), &edges, &[3, 2, 1]), vec![2, 3, 1]);
+    }
+}
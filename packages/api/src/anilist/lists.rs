@@ -0,0 +1,101 @@
+use std::collections::HashSet;
+use serde_json::json;
+use actix_web::http::StatusCode;
+use actix_web::HttpResponse;
+use colourful_logger::Logger;
+use lazy_static::lazy_static;
+use crate::anilist::client::send_query;
+use crate::cache::backend::{cache, cached_json, store_json};
+use crate::cache::keys::user_key;
+use crate::cache::negative::{cache_not_found, cached_not_found};
+
+lazy_static! {
+    static ref logger: Logger = Logger::default();
+}
+
+const LIST_TTL: i64 = 1800;
+
+pub fn list_key(username: &str, media_type: &str) -> String {
+    format!("{}:list:{}", user_key(username), media_type.to_lowercase())
+}
+
+/// Fetches a user's whole anime or manga list, cached under the user so purging the user also drops their lists.
+pub async fn fetch_media_list(username: &str, media_type: &str) -> Result<serde_json::Value, HttpResponse> {
+    let media_type = media_type.to_uppercase();
+    if let Some(ttl) = cached_not_found(&user_key(username)) {
+        logger.debug_single(&format!("{} is cached as not found", username), "Lists");
+        let not_found = json!({"error": "User not found", "errorCode": 404, "dataFrom": "Cache", "leftUntilExpire": ttl});
+        return Err(HttpResponse::NotFound().json(not_found));
+    }
+
+    let redis_key = list_key(username, &media_type);
+    if let Some(list) = cached_json(&redis_key) {
+        logger.debug_single(&format!("Found {} list for {} in cache", media_type, username), "Lists");
+        return Ok(list);
+    }
+
+    let list = match send_query("affinity", json!({"userName": username, "type": media_type}), "Lists").await {
+        Ok(list) => wash_media_list(&list["data"]["MediaListCollection"], &media_type),
+        Err(response) => {
+            if response.status() == StatusCode::NOT_FOUND {
+                cache_not_found(&user_key(username));
+            }
+            return Err(response);
+        }
+    };
+
+    store_json(&redis_key, &list, LIST_TTL);
+    Ok(list)
+}
+
+/// Whether a list lookup would be answered from the cache, either the list itself or the user's not found tombstone.
+pub fn list_cached(username: &str, media_type: &str) -> bool {
+    cache.get(&list_key(username, media_type)).is_ok() || cached_not_found(&user_key(username)).is_some()
+}
+
+/// Media IDs on a user's list, whatever their status.
+pub fn listed_ids(list: &serde_json::Value) -> HashSet<i64> {
+    list["entries"]
+        .as_array()
+        .unwrap_or(&vec![])
+        .iter()
+        .filter_map(|entry| entry["mediaId"].as_i64())
+        .collect()
+}
+
+fn wash_media_list(collection: &serde_json::Value, media_type: &str) -> serde_json::Value {
+    logger.debug_single("Washing up media list data", "Lists");
+    let mut seen: HashSet<i64> = HashSet::new();
+    let mut entries: Vec<serde_json::Value> = Vec::new();
+
+    // Custom lists repeat entries from the status lists, so each media is only kept once
+    for list in collection["lists"].as_array().unwrap_or(&vec![]) {
+        for entry in list["entries"].as_array().unwrap_or(&vec![]) {
+            let Some(media_id) = entry["mediaId"].as_i64() else {
+                continue;
+            };
+
+            if seen.insert(media_id) {
+                entries.push(json!({
+                    "mediaId"   : media_id,
+                    "title"     : entry["media"]["title"]["romaji"],
+                    "status"    : entry["status"],
+                    "score"     : entry["score"],
+                    "genres"    : entry["media"]["genres"],
+                    "tags"      : entry["media"]["tags"],
+                }));
+            }
+        }
+    }
+
+    json!({
+        "user"      : {
+            "name"      : collection["user"]["name"],
+            "avatar"    : collection["user"]["avatar"]["large"],
+            "url"       : collection["user"]["siteUrl"],
+        },
+        "mediaType" : media_type,
+        "entries"   : entries,
+        "dataFrom"  : "API",
+    })
+}
@@ -1,20 +1,32 @@
+use std::collections::HashMap;
 use reqwest::{Client, Response};
 use serde::Deserialize;
 use serde_json::json;
 use actix_web::{web, post, HttpResponse, Responder};
 use colourful_logger::Logger;
+use crate::anilist::client::send_query;
+use crate::anilist::enums::{known_value, labelled, FORMAT_LABELS, MEDIA_TYPES, SEASON_LABELS, SOURCE_LABELS, STATUS_LABELS};
+use crate::anilist::search::MediaFilters;
 use crate::anilist::queries::{get_query, QUERY_URL};
 use lazy_static::lazy_static;
-use crate::cache::redis::Redis;
-use rand::Rng;
+use crate::cache::backend::cache;
+use crate::cache::keys::{media_key, not_found_key, title_key, AIRING_EPISODES, AIRING_PENDING};
+use crate::cache::negative::{cache_not_found, cached_not_found, clear_cached};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use crate::cache::proxy::{get_random_proxy, remove_proxy};
 use crate::global::compare_strings::compare_strings;
+use crate::global::markdown::{html_to_markdown, truncate_sentences, Spoilers};
 
 lazy_static! {
     static ref logger: Logger = Logger::default();
-    static ref redis:  Redis  = Redis::new();
 }
 
+const SHORT_SYNOPSIS_LENGTH: usize = 400;
+const MAX_BATCH_SIZE: usize = 50;
+const TITLE_LANGUAGES: [&str; 4] = ["romaji", "english", "native", "userPreferred"];
+const DEFAULT_TITLE_LANGUAGE: &str = "romaji";
+
 #[derive(Deserialize)]
 struct RelationRequest {
     media_name: String,
@@ -23,14 +35,29 @@ struct RelationRequest {
 
 #[derive(Deserialize)]
 struct MediaRequest {
-    media_id:   i32,
-    media_type: String,
+    media_id:       Option<i32>,
+    media_name:     Option<String>,
+    media_type:     String,
+    refresh:        Option<bool>,
+    link_language:  Option<String>,
+    title_language: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BatchRequest {
+    media_ids:      Vec<i64>,
+    refresh:        Option<bool>,
+    title_language: Option<String>,
 }
 
 #[derive(Deserialize)]
 struct RecommendRequest {
-    media:      String,
-    genres:     Option<Vec<String>>,
+    media:          String,
+    #[serde(flatten)]
+    filters:        MediaFilters,
+    min_score:      Option<i64>,
+    min_popularity: Option<i64>,
+    seed:           Option<u64>,
 }
 
 #[post("/relations")]
@@ -41,11 +68,21 @@ pub async fn relations_search(req: web::Json<RelationRequest>) -> impl Responder
         return HttpResponse::BadRequest().json(bad_json);
     }
 
-    let get_proxy = get_random_proxy(&redis.get_client()).await.unwrap();
+    match fetch_relations(&req.media_name, &req.media_type).await {
+        Ok(relations) => {
+            logger.debug_single("Returning relational data", "Relations");
+            HttpResponse::Ok().json(relations)
+        },
+        Err(response) => response,
+    }
+}
+
+async fn fetch_relations(media_name: &str, media_type: &str) -> Result<serde_json::Value, HttpResponse> {
+    let get_proxy = get_random_proxy().await.unwrap();
     let proxy = reqwest::Proxy::http(get_proxy.clone()).unwrap();
     let client = Client::builder().proxy(proxy).build().unwrap();
     let query:  String = get_query("relation_stats");
-    let json:   serde_json::Value = json!({"query": query, "variables": {"search": req.media_name, "type": req.media_type.to_uppercase()}});
+    let json:   serde_json::Value = json!({"query": query, "variables": {"search": media_name, "type": media_type.to_uppercase()}});
     logger.debug_single("Sending request with relational data", "Relations");
 
     let response: Response = client
@@ -57,70 +94,81 @@ pub async fn relations_search(req: web::Json<RelationRequest>) -> impl Responder
     
     if response.status().as_u16() != 200 {
         if response.status().as_u16() == 403 {
-            let _ = remove_proxy(&redis.get_client(), get_proxy.as_str()).await;
+            let _ = remove_proxy(get_proxy.as_str()).await;
         }
 
-        logger.error_single(format!("Request returned {} when trying to fetch data for {} with type {}", response.status().as_str(), req.media_name, req.media_type).as_str(), "Relations");
+        logger.error_single(format!("Request returned {} when trying to fetch data for {} with type {}", response.status().as_str(), media_name, media_type).as_str(), "Relations");
         let bad_json = json!({"error": "Request returned an error", "errorCode": response.status().as_u16()});
-        return HttpResponse::BadRequest().json(bad_json);
+        return Err(HttpResponse::BadRequest().json(bad_json));
     }
         
     let relations = response.json::<serde_json::Value>().await.unwrap();
-    let relations = wash_relation_data(req.media_name.to_string(), relations).await;
-    logger.debug_single("Returning relational data", "Relations");
-    HttpResponse::Ok().json(relations)
+    Ok(wash_relation_data(media_name.to_string(), relations).await)
 }
 
 #[post("/recommend")]
 async fn recommend(req: web::Json<RecommendRequest>) -> impl Responder {
-    let genres = req.genres.clone().unwrap_or(vec![]);
-    let mut rng = rand::rng();
+    let mut variables = match recommendation_filters(&req) {
+        Ok(variables) => variables,
+        Err(error) => return HttpResponse::BadRequest().json(error),
+    };
 
-    let get_proxy = get_random_proxy(&redis.get_client()).await.unwrap();
-    let proxy = reqwest::Proxy::http(get_proxy.clone()).unwrap();
-    let client = Client::builder().proxy(proxy).build().unwrap();
-    let recommendation_amount_query = get_query("recommendation_amount");
-    let json = json!({
-        "query": recommendation_amount_query,
-        "variables": {
-            "page": 1,
-            "perPage": 50
-        }
-    });
+    // The seed is echoed back so the same pick can be reproduced while the matching media does not change
+    let seed = req.seed.unwrap_or_else(|| rand::rng().random());
+    let mut rng = StdRng::seed_from_u64(seed);
 
-    logger.debug_single(format!("Sending request to client with JSON query").as_str(), "Recommend");
-    let response: Response = client
-        .post(QUERY_URL)
-        .json(&json)
-        .send()
-        .await
-        .unwrap();
-    
-    if response.status().as_u16() != 200 {
-        if response.status().as_u16() == 403 {
-            let _ = remove_proxy(&redis.get_client(), get_proxy.as_str()).await;
+    let first_page = match send_query("recommendation", variables.clone(), "Recommend").await {
+        Ok(first_page) => first_page,
+        Err(response) => return response,
+    };
+
+    let last_page = first_page["data"]["Page"]["pageInfo"]["lastPage"].as_i64().unwrap_or(1).max(1);
+    logger.debug_single(&format!("Last Page set to : {}", last_page), "Recommend");
+
+    let page = rng.random_range(1..=last_page);
+    let mut ids = recommendation_ids(&first_page);
+    if page > 1 {
+        variables["page"] = page.into();
+        let page_ids = send_query("recommendation", variables, "Recommend").await.map(|page| recommendation_ids(&page)).unwrap_or_default();
+        if page_ids.is_empty() {
+            logger.debug_single("Bad page received, using the first page instead", "Recommend");
+        } else {
+            ids = page_ids;
         }
+    }
 
-        let status = response.status();
-        let response_text = response.text().await.unwrap();
-        logger.error_single(format!("First request returned {} : {:?}", status, response_text).as_str(), "Recommend");
-        let bad_json = json!({"error": "Request returned an error", "errorCode": status.as_u16(), "response": response_text});
-        return HttpResponse::BadRequest().json(bad_json);
+    if ids.is_empty() {
+        logger.error_single("No recommendations found", "Recommend");
+        return HttpResponse::NotFound().json(json!({"error": "No recommendations found", "seed": seed}));
     }
 
-    let recommend_amount = response.json::<serde_json::Value>().await.unwrap();
-    let data = &recommend_amount["data"]["Page"];
-    let last_page = data["pageInfo"]["lastPage"].as_i64().unwrap_or(1);
-    logger.debug_single(&format!("Last Page set to : {}", last_page), "Recommend");
+    let random_choice = rng.random_range(0..ids.len());
+    HttpResponse::Ok()
+        .insert_header(("X-Recommend-Seed", seed.to_string()))
+        .json(json!(ids[random_choice]))
+}
 
-    let pages = rng.random_range(1..last_page);
-    let mut recommend = get_recommendation(pages, genres.clone(), req.media.clone()).await;
-    if recommend.get("error").is_some() {
-        logger.debug_single("Bad JSON received, setting pages to 1 and retrying", "Recommend");
-        recommend = get_recommendation(1, genres, req.media.clone()).await;
-    }
-    
-    HttpResponse::Ok().json(recommend)
+fn recommendation_filters(req: &RecommendRequest) -> Result<serde_json::Value, serde_json::Value> {
+    let Some(media_type) = known_value(&req.media, &MEDIA_TYPES) else {
+        return Err(json!({"error": "media must be ANIME or MANGA"}));
+    };
+
+    req.filters.apply(json!({
+        "page"              : 1,
+        "perPage"           : 50,
+        "type"              : media_type,
+        "scoreAbove"        : req.min_score.map(|score| score - 1),
+        "popularityAbove"   : req.min_popularity.map(|popularity| popularity - 1),
+    }))
+}
+
+fn recommendation_ids(recommendations: &serde_json::Value) -> Vec<i64> {
+    recommendations["data"]["Page"]["media"]
+        .as_array()
+        .unwrap_or(&vec![])
+        .iter()
+        .filter_map(|media| media["id"].as_i64())
+        .collect()
 }
 
 #[post("/media")]
@@ -132,27 +180,110 @@ pub async fn media_search(req: web::Json<MediaRequest>) -> impl Responder {
         return HttpResponse::BadRequest().json(bad_json);
     }
 
-    match redis.get(req.media_id.to_string()) {
-        Ok(data) => {
-            logger.debug_single("Found media data in cache. Returning cached data", "Media");
-            let mut media_data: serde_json::Value = serde_json::from_str(data.as_str()).unwrap();
-            media_data["dataFrom"] = "Cache".into();
-            if let Some(_airing) = media_data["airing"].as_array().and_then(|arr| arr.get(0)) {
-                media_data["airing"][0]["timeUntilAiring"] = redis.ttl(req.media_id.to_string()).unwrap().into();
+    let language = match req.title_language.as_deref().map(title_language).transpose() {
+        Ok(language) => language,
+        Err(error) => return HttpResponse::BadRequest().json(error),
+    };
+
+    let refresh = req.refresh.unwrap_or(false);
+    let (media_id, match_confidence) = match (req.media_id, &req.media_name) {
+        (Some(media_id), _) => (media_id as i64, None),
+        (None, Some(media_name)) if !media_name.trim().is_empty() => {
+            match resolve_media_title(media_name, &req.media_type, refresh).await {
+                Ok((media_id, similarity)) => (media_id, Some(similarity)),
+                Err(response) => return response,
             }
-            media_data["leftUntilExpire"] = redis.ttl(req.media_id.to_string()).unwrap().into();
-            return HttpResponse::Ok().json(media_data);
         },
-        Err(_) => {
+        _ => {
+            logger.error_single("No media ID or name was included", "Media");
+            let bad_json = json!({"error": "No media ID or name was included"});
+            return HttpResponse::BadRequest().json(bad_json);
+        }
+    };
+
+    match fetch_media(media_id, &req.media_type, refresh).await {
+        Ok(mut media) => {
+            if let Some(match_confidence) = match_confidence {
+                media["matchConfidence"] = match_confidence.into();
+            }
+            if let Some(language) = &req.link_language {
+                filter_links(&mut media, language);
+            }
+            if let Some(language) = language {
+                set_display_title(&mut media, language);
+            }
+            HttpResponse::Ok().json(media)
+        },
+        Err(response) => response,
+    }
+}
+
+/// Resolves a title to the closest media ID using the same ranking as `/relations`, caching the match for a week.
+async fn resolve_media_title(media_name: &str, media_type: &str, refresh: bool) -> Result<(i64, f64), HttpResponse> {
+    let redis_key = title_key(media_type, media_name);
+    if refresh {
+        clear_cached(&redis_key);
+    }
+
+    if let Some(ttl) = cached_not_found(&redis_key) {
+        logger.debug_single(&format!("{} is cached as not matching any media", media_name), "Media");
+        let not_found = json!({"error": "No media matched the title", "errorCode": 404, "dataFrom": "Cache", "leftUntilExpire": ttl});
+        return Err(HttpResponse::NotFound().json(not_found));
+    }
+
+    if let Ok(data) = cache.get(&redis_key) {
+        let title: serde_json::Value = serde_json::from_str(data.as_str()).unwrap();
+        if let (Some(media_id), Some(similarity)) = (title["id"].as_i64(), title["similarity"].as_f64()) {
+            logger.debug_single(&format!("Found cached media ID {} for {}", media_id, media_name), "Media");
+            return Ok((media_id, similarity));
+        }
+    }
+
+    let relations = fetch_relations(media_name, media_type).await?;
+    let Some(closest) = relations["relations"].as_array().and_then(|relations| relations.first()) else {
+        logger.warn_single(&format!("No media matched the title {}", media_name), "Media");
+        cache_not_found(&redis_key);
+        let not_found = json!({"error": "No media matched the title", "errorCode": 404, "dataFrom": "API"});
+        return Err(HttpResponse::NotFound().json(not_found));
+    };
+
+    let media_id = closest["id"].as_i64().unwrap();
+    let similarity = closest["similarity"].as_f64().unwrap_or(0.0);
+    let _ = cache.set(&redis_key, &json!({"id": media_id, "similarity": similarity}).to_string());
+    let _ = cache.expire(&redis_key, 604800);
+
+    Ok((media_id, similarity))
+}
+
+pub async fn fetch_media(media_id: i64, media_type: &str, refresh: bool) -> Result<serde_json::Value, HttpResponse> {
+    let redis_key = media_key(media_id);
+    let typed_key = typed_media_key(media_id, media_type);
+    if refresh {
+        logger.debug_single(&format!("Refreshing cached data for media {}", media_id), "Media");
+        clear_cached_media(media_id);
+    }
+
+    if let Some(ttl) = cached_not_found(&redis_key).or_else(|| cached_not_found(&typed_key)) {
+        logger.debug_single(&format!("Media {} is cached as not found", media_id), "Media");
+        let not_found = json!({"error": "Media not found", "errorCode": 404, "dataFrom": "Cache", "leftUntilExpire": ttl});
+        return Err(HttpResponse::NotFound().json(not_found));
+    }
+
+    match cached_media(&redis_key) {
+        Some(media_data) => {
+            logger.debug_single("Found media data in cache. Returning cached data", "Media");
+            return Ok(media_data);
+        },
+        None => {
             logger.debug_single("No media data found in cache", "Media");
         }
     }
 
-    let get_proxy = get_random_proxy(&redis.get_client()).await.unwrap();
+    let get_proxy = get_random_proxy().await.unwrap();
     let proxy = reqwest::Proxy::http(get_proxy.clone()).unwrap();
     let client = Client::builder().proxy(proxy).build().unwrap();
     let query:  String = get_query("search");
-    let json:   serde_json::Value = json!({"query": query, "variables": {"id": req.media_id, "type": req.media_type.to_uppercase()}});
+    let json:   serde_json::Value = json!({"query": query, "variables": {"id": media_id, "type": media_type.to_uppercase()}});
     logger.debug_single("Sending request with relational data", "Media");
 
     let response: Response = client
@@ -164,97 +295,206 @@ pub async fn media_search(req: web::Json<MediaRequest>) -> impl Responder {
 
     if response.status().as_u16() != 200 {
         if response.status().as_u16() == 403 {
-            let _ = remove_proxy(&redis.get_client(), get_proxy.as_str()).await;
+            let _ = remove_proxy(get_proxy.as_str()).await;
         }
 
-        logger.error_single(format!("Request returned {} when trying to fetch data for {} with type {}", response.status().as_str(), req.media_id, req.media_type).as_str(), "Media");
+        if response.status().as_u16() == 404 {
+            logger.warn_single(&format!("Media {} with type {} was not found", media_id, media_type), "Media");
+            cache_not_found(&typed_key);
+            let not_found = json!({"error": "Media not found", "errorCode": 404, "dataFrom": "API"});
+            return Err(HttpResponse::NotFound().json(not_found));
+        }
+
+        logger.error_single(format!("Request returned {} when trying to fetch data for {} with type {}", response.status().as_str(), media_id, media_type).as_str(), "Media");
         let bad_json = json!({"error": "Request returned an error", "errorCode": response.status().as_u16()});
-        return HttpResponse::BadRequest().json(bad_json);
+        return Err(HttpResponse::BadRequest().json(bad_json));
     }
         
     let media: serde_json::Value = response.json::<serde_json::Value>().await.unwrap();
     let media: serde_json::Value = wash_media_data(media).await;
+    cache_media(&media);
 
-    let _ = redis.set(media["id"].to_string(), media.clone().to_string());
-    if media["airing"].as_array().unwrap().len() > 0 {
-        logger.debug_single(&format!("{} is releasing, expiring cache when next episode is aired.", media["romaji"]), "Media");
-        let _ = redis.expire(media["id"].to_string(), media["airing"][0]["timeUntilAiring"].as_i64().unwrap());
-    } else {
-        logger.debug_single(&format!("{} is not releasing, keep data for a week.", media["romaji"]), "Media");
-        let _ = redis.expire(media["id"].to_string(), 86400);
-    }
+    Ok(media)
+}
 
-    HttpResponse::Ok().json(media)
+/// A 404 for one type says nothing about the other, so `fetch_media` keeps its tombstones per type.
+fn typed_media_key(media_id: i64, media_type: &str) -> String {
+    format!("{}:{}", media_key(media_id), media_type.to_uppercase())
 }
 
-async fn get_recommendation(pages: i64, genres: Vec<String>, media: String) -> serde_json::Value {
-    let mut rng = rand::rng();
-    let get_proxy = get_random_proxy(&redis.get_client()).await.unwrap();
-    let proxy = reqwest::Proxy::http(get_proxy.clone()).unwrap();
-    let client = Client::builder().proxy(proxy).build().unwrap();
+/// Clears a cached media along with its untyped and per type tombstones.
+fn clear_cached_media(media_id: i64) {
+    clear_cached(&media_key(media_id));
+    for media_type in MEDIA_TYPES {
+        let _ = cache.delete(&not_found_key(&typed_media_key(media_id, media_type)));
+    }
+}
 
-    let recommendation_query = get_query("recommendation");
-    let json = json!({
-        "query": recommendation_query, 
-        "variables": {
-            "type": media, 
-            "genres": genres, 
-            "page": pages, 
-            "perPage": 50
-    }});
-
-    logger.debug_single(format!("Sending request to client with JSON query:").as_str(), "Recommend");
-    let response: Response = client
-                .post(QUERY_URL)
-                .json(&json)
-                .send()
-                .await
-                .unwrap();
-    
-    if response.status().as_u16() != 200 {
-        if response.status().as_u16() == 403 {
-            let _ = remove_proxy(&redis.get_client(), get_proxy.as_str()).await;
+fn cached_media(redis_key: &str) -> Option<serde_json::Value> {
+    let data = cache.get(redis_key).ok()?;
+    let mut media_data: serde_json::Value = serde_json::from_str(data.as_str()).unwrap();
+    media_data["dataFrom"] = "Cache".into();
+    if let Some(_airing) = media_data["airing"].as_array().and_then(|arr| arr.get(0)) {
+        media_data["airing"][0]["timeUntilAiring"] = cache.ttl(redis_key).unwrap().into();
+    }
+    media_data["leftUntilExpire"] = cache.ttl(redis_key).unwrap().into();
+    Some(media_data)
+}
+
+#[post("/media/batch")]
+pub async fn media_batch(req: web::Json<BatchRequest>) -> impl Responder {
+    let mut media_ids: Vec<i64> = Vec::new();
+    for media_id in &req.media_ids {
+        if !media_ids.contains(media_id) {
+            media_ids.push(*media_id);
         }
+    }
 
-        let status = response.status();
-        let response_text = response.text().await.unwrap();
-        logger.error_single(format!("Second request returned {} : {:?}", status, response_text).as_str(), "Recommend");
-        let bad_json = json!({"error": "Request returned an error", "errorCode": status.as_u16(), "response": response_text});
-        return bad_json;
+    if media_ids.is_empty() || media_ids.len() > MAX_BATCH_SIZE {
+        logger.error_single(&format!("Batch of {} media IDs was requested", media_ids.len()), "Batch");
+        let bad_json = json!({"error": format!("Between 1 and {} media IDs must be included", MAX_BATCH_SIZE)});
+        return HttpResponse::BadRequest().json(bad_json);
     }
 
-    let recommeneded_ids = response.json::<serde_json::Value>().await.unwrap();
-    let recommeneded_ids = &recommeneded_ids["data"]["Page"]["media"];
+    let language = match req.title_language.as_deref().map(title_language).transpose() {
+        Ok(language) => language,
+        Err(error) => return HttpResponse::BadRequest().json(error),
+    };
+
+    let refresh = req.refresh.unwrap_or(false);
+    let mut found: HashMap<i64, serde_json::Value> = HashMap::new();
+    let mut not_found: Vec<i64> = Vec::new();
+    let mut missing: Vec<i64> = Vec::new();
 
-    logger.debug("Returning recommendations", "Recommend", false, recommeneded_ids.clone());
+    for media_id in &media_ids {
+        let redis_key = media_key(media_id);
+        if refresh {
+            clear_cached_media(*media_id);
+        }
 
-    let mut ids = Vec::new();
-    for media in recommeneded_ids.as_array().unwrap() {
-        if let Some(id) = media["id"].as_i64() {
-            ids.push(id);
+        if cached_not_found(&redis_key).is_some() {
+            not_found.push(*media_id);
+        } else if let Some(media) = cached_media(&redis_key) {
+            found.insert(*media_id, media);
+        } else {
+            missing.push(*media_id);
         }
     }
 
-    if ids.len() == 0 {
-        logger.error_single("No recommendations found", "Recommend");
-        let bad_json = json!({"error": "No recommendations found"});
-        return bad_json;
+    let cached = found.len();
+    if !missing.is_empty() {
+        logger.debug_single(&format!("Fetching {} uncached media in one request", missing.len()), "Batch");
+        let variables = json!({"ids": missing, "perPage": MAX_BATCH_SIZE});
+        let batch = match send_query("media_batch", variables, "Batch").await {
+            Ok(batch) => batch,
+            Err(response) => return response,
+        };
+
+        for media in batch["data"]["Page"]["media"].as_array().unwrap_or(&vec![]) {
+            let media = wash_media_node(media.clone()).await;
+            cache_media(&media);
+            found.insert(media["id"].as_i64().unwrap(), media);
+        }
+
+        for media_id in &missing {
+            if !found.contains_key(media_id) {
+                logger.warn_single(&format!("Media {} was not returned in batch", media_id), "Batch");
+                cache_not_found(&media_key(media_id));
+                not_found.push(*media_id);
+            }
+        }
     }
-    let random_choice = rng.random_range(0..ids.len());
-    json!(ids[random_choice])
+
+    let fetched = found.len() - cached;
+    let mut media: Vec<serde_json::Value> = media_ids.iter().filter_map(|media_id| found.remove(media_id)).collect();
+    if let Some(language) = language {
+        media.iter_mut().for_each(|media| set_display_title(media, language));
+    }
+
+    HttpResponse::Ok().json(json!({
+        "media"     : media,
+        "notFound"  : not_found,
+        "cached"    : cached,
+        "fetched"   : fetched,
+    }))
+}
+
+/// Data cached alongside a media (characters, reviews...) is kept for as long as the media itself, so both refresh once the next episode airs.
+pub fn media_ttl(media_id: i64) -> i64 {
+    match cache.ttl(&media_key(media_id)) {
+        Ok(ttl) if ttl > 0 => ttl,
+        _ => 86400,
+    }
+}
+
+pub fn cache_media(media: &serde_json::Value) {
+    let redis_key = media_key(&media["id"]);
+    let _ = cache.set(&redis_key, &media.to_string());
+    if media["airing"].as_array().is_some_and(|airing| !airing.is_empty()) {
+        logger.debug_single(&format!("{} is releasing, expiring cache when next episode is aired.", media["romaji"]), "Media");
+        let _ = cache.expire(&redis_key, media["airing"][0]["timeUntilAiring"].as_i64().unwrap());
+        schedule_airing(media);
+    } else {
+        logger.debug_single(&format!("{} is not releasing, keep data for a week.", media["romaji"]), "Media");
+        let _ = cache.expire(&redis_key, 86400);
+    }
+}
+
+fn schedule_airing(media: &serde_json::Value) {
+    let next_episode = &media["airing"][0];
+    let Some(airing_at) = next_episode["airingAt"].as_i64() else {
+        return;
+    };
+
+    let member = format!("{}:{}", media["id"], next_episode["episode"]);
+    let event = json!({
+        "mediaId"   : media["id"],
+        "title"     : media["romaji"],
+        "episode"   : next_episode["episode"],
+        "airingAt"  : airing_at,
+    });
+
+    let _ = cache.hash_set(AIRING_EPISODES, &member, &event.to_string());
+    let _ = cache.schedule(AIRING_PENDING, &member, airing_at);
 }
 
 async fn wash_media_data(media_data: serde_json::Value) -> serde_json::Value {
+    wash_media_node(media_data["data"]["Media"].clone()).await
+}
+
+pub async fn wash_media_node(data: serde_json::Value) -> serde_json::Value {
     logger.debug_single("Washing up media data", "Media");
-    let mut data = media_data["data"]["Media"].clone();
 
-    if data["status"] == "NOT_YET_RELEASED" {
-        data["status"] = "Not Yet Released".into();
-    }
+    let synopsis = match data["description"].as_str() {
+        Some(description) => {
+            let spoiler_free = html_to_markdown(description, Spoilers::Strip);
+            json!({
+                "full"          : html_to_markdown(description, Spoilers::Wrap),
+                "short"         : truncate_sentences(&spoiler_free, SHORT_SYNOPSIS_LENGTH),
+                "spoilerFree"   : spoiler_free,
+            })
+        },
+        None => serde_json::Value::Null,
+    };
+
+    let mal_url = match (data["idMal"].as_i64(), data["type"].as_str()) {
+        (Some(id_mal), Some(media_type)) => Some(format!("https://myanimelist.net/{}/{}", media_type.to_lowercase(), id_mal)),
+        _ => None,
+    };
 
     let washed_data: serde_json::Value = json!({
         "id"            : data["id"],
+        "idMal"         : data["idMal"],
+        "malUrl"        : mal_url,
         "romaji"        : data["title"]["romaji"],
+        "titles"        : {
+            "romaji"        : data["title"]["romaji"],
+            "english"       : data["title"]["english"],
+            "native"        : data["title"]["native"],
+            "userPreferred" : data["title"]["userPreferred"],
+        },
+        "synonyms"      : data["synonyms"],
+        "displayTitle"  : display_title(&data["title"], DEFAULT_TITLE_LANGUAGE),
         "airing"        : data["airingSchedule"]["nodes"],
         "averageScore"  : data["averageScore"],
         "meanScore"     : data["meanScore"],
@@ -264,14 +504,20 @@ async fn wash_media_data(media_data: serde_json::Value) -> serde_json::Value {
         "episodes"      : data["episodes"],
         "chapters"      : data["chapters"],
         "volumes"       : data["volumes"],
-        "format"        : data["format"],
+        "format"        : labelled(&data["format"], &FORMAT_LABELS),
+        "season"        : labelled(&data["season"], &SEASON_LABELS),
+        "source"        : labelled(&data["source"], &SOURCE_LABELS),
         "genres"        : data["genres"],
+        "synopsis"      : synopsis,
+        "stats"         : wash_media_stats(&data["stats"]),
+        "links"         : wash_external_links(&data["externalLinks"]),
+        "streamingEpisodes" : wash_streaming_episodes(&data["streamingEpisodes"]),
         "popularity"    : data["popularity"],
         "favourites"    : data["favourites"],
-        "status"        : data["status"],
+        "status"        : labelled(&data["status"], &STATUS_LABELS),
         "url"           : data["siteUrl"],
-        "endDate"       : format!("{}/{}/{}", data["endDate"]["day"], data["endDate"]["month"], data["endDate"]["year"]),
-        "startDate"     : format!("{}/{}/{}", data["startDate"]["day"], data["startDate"]["month"], data["startDate"]["year"]),
+        "endDate"       : fuzzy_date(&data["endDate"]),
+        "startDate"     : fuzzy_date(&data["startDate"]),
         "dataFrom"      : "API",
     });
 
@@ -279,6 +525,164 @@ async fn wash_media_data(media_data: serde_json::Value) -> serde_json::Value {
     washed_data
 }
 
+/// Anilist dates can be partial, so the ISO-8601 string only goes as far as the first missing part (e.g. `2025-04`).
+fn fuzzy_date(date: &serde_json::Value) -> serde_json::Value {
+    let (year, month, day) = (date["year"].as_i64(), date["month"].as_i64(), date["day"].as_i64());
+    if year.is_none() && month.is_none() && day.is_none() {
+        return serde_json::Value::Null;
+    }
+
+    let iso = match (year, month, day) {
+        (Some(year), Some(month), Some(day)) => Some(format!("{:04}-{:02}-{:02}", year, month, day)),
+        (Some(year), Some(month), None) => Some(format!("{:04}-{:02}", year, month)),
+        (Some(year), _, _) => Some(format!("{:04}", year)),
+        _ => None,
+    };
+
+    json!({
+        "year"  : year,
+        "month" : month,
+        "day"   : day,
+        "iso"   : iso,
+    })
+}
+
+/// Picks the title in the requested language, falling back to romaji, english and then native when it is missing.
+fn display_title(titles: &serde_json::Value, language: &str) -> serde_json::Value {
+    [language, "romaji", "english", "native"]
+        .iter()
+        .map(|language| &titles[*language])
+        .find(|title| title.as_str().is_some_and(|title| !title.is_empty()))
+        .cloned()
+        .unwrap_or(serde_json::Value::Null)
+}
+
+/// Matches a requested title language regardless of case, returning the key it has under `titles`.
+pub fn title_language(language: &str) -> Result<&'static str, serde_json::Value> {
+    match TITLE_LANGUAGES.iter().find(|known| known.eq_ignore_ascii_case(&language.replace('_', ""))) {
+        Some(language) => Ok(language),
+        None => {
+            logger.error_single(&format!("Unknown title language : {}", language), "Media");
+            Err(json!({"error": "Unknown title language", "languages": TITLE_LANGUAGES}))
+        }
+    }
+}
+
+pub fn set_display_title(media: &mut serde_json::Value, language: &str) {
+    media["displayTitle"] = display_title(&media["titles"], language);
+}
+
+/// Groups external links by their Anilist link type, dropping links Anilist has marked as disabled.
+fn wash_external_links(links: &serde_json::Value) -> serde_json::Value {
+    let mut grouped = json!({"streaming": [], "info": [], "social": []});
+
+    for link in links.as_array().unwrap_or(&vec![]) {
+        if link["isDisabled"].as_bool().unwrap_or(false) {
+            continue;
+        }
+
+        let group = match link["type"].as_str() {
+            Some("STREAMING") => "streaming",
+            Some("SOCIAL") => "social",
+            _ => "info",
+        };
+
+        grouped[group].as_array_mut().unwrap().push(json!({
+            "site"      : link["site"],
+            "url"       : link["url"],
+            "language"  : link["language"],
+            "icon"      : link["icon"],
+            "color"     : link["color"],
+        }));
+    }
+
+    grouped
+}
+
+fn wash_streaming_episodes(episodes: &serde_json::Value) -> serde_json::Value {
+    episodes
+        .as_array()
+        .unwrap_or(&vec![])
+        .iter()
+        .map(|episode| json!({
+            "title"     : episode["title"],
+            "thumbnail" : episode["thumbnail"],
+            "url"       : episode["url"],
+            "site"      : episode["site"],
+        }))
+        .collect()
+}
+
+/// Keeps links for the requested language, links without a language (e.g. official sites) are always kept.
+fn filter_links(media: &mut serde_json::Value, language: &str) {
+    let Some(groups) = media["links"].as_object_mut() else {
+        return;
+    };
+
+    for links in groups.values_mut() {
+        if let Some(links) = links.as_array_mut() {
+            links.retain(|link| link["language"].as_str().is_none_or(|link_language| link_language.eq_ignore_ascii_case(language)));
+        }
+    }
+}
+
+fn percentage(amount: i64, total: i64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    (amount as f64 / total as f64 * 1000.0).round() / 10.0
+}
+
+/// Score histogram normalised against its tallest bar, so it can be charted as is.
+fn wash_media_stats(stats: &serde_json::Value) -> serde_json::Value {
+    let empty_vec = vec![];
+    let mut scores: Vec<(i64, i64)> = stats["scoreDistribution"]
+        .as_array()
+        .unwrap_or(&empty_vec)
+        .iter()
+        .filter_map(|bucket| Some((bucket["score"].as_i64()?, bucket["amount"].as_i64().unwrap_or(0))))
+        .collect();
+    scores.sort_by_key(|(score, _)| *score);
+
+    let scored: i64 = scores.iter().map(|(_, amount)| amount).sum();
+    let tallest = scores.iter().map(|(_, amount)| *amount).max().unwrap_or(0);
+    let histogram: Vec<serde_json::Value> = scores
+        .iter()
+        .map(|(score, amount)| json!({
+            "score"         : score,
+            "amount"        : amount,
+            "percentage"    : percentage(*amount, scored),
+            "normalized"    : if tallest == 0 { 0.0 } else { (*amount as f64 / tallest as f64 * 1000.0).round() / 1000.0 },
+        }))
+        .collect();
+
+    let statuses: Vec<(String, i64)> = stats["statusDistribution"]
+        .as_array()
+        .unwrap_or(&empty_vec)
+        .iter()
+        .filter_map(|bucket| Some((bucket["status"].as_str()?.to_string(), bucket["amount"].as_i64().unwrap_or(0))))
+        .collect();
+
+    let listed: i64 = statuses.iter().map(|(_, amount)| amount).sum();
+    let amount_of = |status: &str| statuses.iter().find(|(name, _)| name == status).map(|(_, amount)| *amount).unwrap_or(0);
+    let (completed, dropped) = (amount_of("COMPLETED"), amount_of("DROPPED"));
+
+    let mut status_distribution = serde_json::Map::new();
+    for (status, amount) in &statuses {
+        status_distribution.insert(status.clone(), json!({"amount": amount, "percentage": percentage(*amount, listed)}));
+    }
+
+    json!({
+        "scored"                : scored,
+        "listed"                : listed,
+        "scoreDistribution"     : histogram,
+        "statusDistribution"    : status_distribution,
+        "completedPercentage"   : percentage(completed, listed),
+        "droppedPercentage"     : percentage(dropped, listed),
+        "completionRate"        : percentage(completed, completed + dropped),
+    })
+}
+
 async fn wash_relation_data(parsed_string: String, relation_data: serde_json::Value) -> serde_json::Value {
     logger.debug_single("Washing up relational data", "Relations");
     let data: &serde_json::Value = &relation_data["data"]["Page"]["media"];
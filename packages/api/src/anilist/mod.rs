@@ -1,3 +1,16 @@
 pub mod queries;
+pub mod client;
 pub mod user;
-pub mod media;
\ No newline at end of file
+pub mod media;
+pub mod characters;
+pub mod studio;
+pub mod schedule;
+pub mod season;
+pub mod rankings;
+pub mod franchise;
+pub mod reviews;
+pub mod enums;
+pub mod search;
+pub mod lists;
+pub mod recommendations;
+pub mod affinity;
\ No newline at end of file
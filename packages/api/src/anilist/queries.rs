@@ -1,48 +1,86 @@
 pub const QUERY_URL: &str = "https://graphql.anilist.co";
 
+/// Every field the media washers read, shared by each query that returns full media.
+const MEDIA_FIELDS: &str = "
+    fragment mediaFields on Media {
+        id
+        idMal
+        type
+        season
+        format
+        source
+        episodes
+        chapters
+        volumes
+        duration
+        description
+        status
+        genres
+        averageScore
+        meanScore
+        popularity
+        siteUrl
+        favourites
+        bannerImage
+        startDate {
+            year
+            month
+            day
+        }
+        endDate {
+            year
+            month
+            day
+        }
+        airingSchedule(notYetAired: true){
+            nodes{
+                timeUntilAiring
+                airingAt
+                episode
+                }
+        }
+        coverImage{
+            extraLarge
+        }
+        externalLinks {
+            site
+            url
+            type
+            language
+            color
+            icon
+            isDisabled
+        }
+        streamingEpisodes {
+            title
+            thumbnail
+            url
+            site
+        }
+        stats {
+            scoreDistribution {
+                score
+                amount
+            }
+            statusDistribution {
+                status
+                amount
+            }
+        }
+        synonyms
+        title{
+            romaji
+            english
+            native
+            userPreferred
+        }
+    }";
+
 pub fn get_query(query_name: &str) -> String {
     let search: &str = "
     query ($id: Int, $search: String, $type: MediaType) {
     Media (id: $id, search: $search, type: $type) {
-            id
-            season
-            format
-            episodes
-            chapters
-            volumes
-            duration
-            description
-            status
-            genres
-            averageScore
-            meanScore
-            popularity
-            siteUrl
-            favourites
-            bannerImage
-            startDate {
-                year
-                month
-                day
-            }
-            endDate {
-                year
-                month
-                day
-            }
-            airingSchedule(notYetAired: true){
-                nodes{
-                    timeUntilAiring
-                    episode
-                    }
-            }
-            coverImage{
-                extraLarge
-            }
-            title{
-                romaji
-                native
-            }
+            ...mediaFields
         }
     }
     ";
@@ -152,47 +190,491 @@ pub fn get_query(query_name: &str) -> String {
                     status
                     score(format: POINT_100)
                     mediaId
-
+                    media {
+                        title {
+                            romaji
+                        }
+                        genres
+                        tags {
+                            name
+                            rank
+                        }
+                    }
                 }
             }
         }
     }
     ";
 
-    let recommendation_amount: &str =  "
-        query ($page: Int, $perPage: Int) {
+    let recommendation: &str =  "
+        query ($type: MediaType, $page: Int, $perPage: Int, $genres: [String], $excludedGenres: [String], $tags: [String], $excludedTags: [String], $formats: [MediaFormat], $scoreAbove: Int, $popularityAbove: Int, $startedAfter: FuzzyDateInt, $startedBefore: FuzzyDateInt, $isAdult: Boolean) {
         Page(page: $page, perPage: $perPage) {
             pageInfo {
                 hasNextPage,
                 lastPage,
             }
+            media(type: $type, genre_in: $genres, genre_not_in: $excludedGenres, tag_in: $tags, tag_not_in: $excludedTags, format_in: $formats, averageScore_greater: $scoreAbove, popularity_greater: $popularityAbove, startDate_greater: $startedAfter, startDate_lesser: $startedBefore, isAdult: $isAdult, sort: ID) {
+                id
+            }
+            }
+        }";
+
+    let airing_warmup: &str = "
+    query ($page: Int, $perPage: Int, $season: MediaSeason, $seasonYear: Int, $status: MediaStatus, $type: MediaType, $sort: [MediaSort]) {
+    Page (page: $page, perPage: $perPage) {
+        pageInfo {
+            hasNextPage
+        }
+        media (season: $season, seasonYear: $seasonYear, status: $status, type: $type, sort: $sort) {
+            ...mediaFields
+            }
+        }
+    }";
+
+    let characters: &str = "
+    query ($id: Int, $page: Int, $perPage: Int, $language: StaffLanguage) {
+    Media (id: $id) {
+        id
+        characters (page: $page, perPage: $perPage, sort: [ROLE, RELEVANCE, ID]) {
+            pageInfo {
+                total
+                currentPage
+                lastPage
+                hasNextPage
+            }
+            edges {
+                role
+                node {
+                    id
+                    siteUrl
+                    favourites
+                    name {
+                        full
+                        native
+                    }
+                    image {
+                        large
+                    }
+                }
+                voiceActors (language: $language, sort: [RELEVANCE, ID]) {
+                    id
+                    siteUrl
+                    languageV2
+                    name {
+                        full
+                        native
+                    }
+                    image {
+                        large
+                    }
+                }
+            }
+            }
+        }
+    }";
+
+    let staff: &str = "
+    query ($id: Int, $page: Int, $perPage: Int) {
+    Media (id: $id) {
+        id
+        staff (page: $page, perPage: $perPage, sort: [RELEVANCE, ID]) {
+            pageInfo {
+                total
+                currentPage
+                lastPage
+                hasNextPage
+            }
+            edges {
+                role
+                node {
+                    id
+                    siteUrl
+                    languageV2
+                    primaryOccupations
+                    name {
+                        full
+                        native
+                    }
+                    image {
+                        large
+                    }
+                }
+            }
+            }
+        }
+    }";
+
+    let studio_search: &str = "
+    query ($search: String, $page: Int, $perPage: Int) {
+    Page (page: $page, perPage: $perPage) {
+        studios (search: $search) {
+            id
+            name
+            isAnimationStudio
+            favourites
+            siteUrl
+            }
+        }
+    }";
+
+    let studio: &str = "
+    query ($id: Int, $page: Int, $perPage: Int, $sort: [MediaSort], $isMain: Boolean) {
+    Studio (id: $id) {
+        id
+        name
+        isAnimationStudio
+        favourites
+        siteUrl
+        media (page: $page, perPage: $perPage, sort: $sort, isMain: $isMain) {
+            pageInfo {
+                total
+                currentPage
+                lastPage
+                hasNextPage
+            }
+            edges {
+                isMainStudio
+                node {
+                    id
+                    type
+                    format
+                    status
+                    seasonYear
+                    averageScore
+                    meanScore
+                    popularity
+                    siteUrl
+                    startDate {
+                        year
+                    }
+                    title {
+                        romaji
+                        english
+                    }
+                    coverImage {
+                        large
+                    }
+                }
+            }
+            }
+        }
+    }";
+
+    let airing_schedule: &str = "
+    query ($page: Int, $perPage: Int, $start: Int, $end: Int) {
+    Page (page: $page, perPage: $perPage) {
+        pageInfo {
+            hasNextPage
+        }
+        airingSchedules (airingAt_greater: $start, airingAt_lesser: $end, sort: TIME) {
+            id
+            episode
+            airingAt
             media {
                 id
+                type
+                format
+                isAdult
+                popularity
+                siteUrl
+                title {
+                    romaji
+                    english
+                    native
+                }
+                coverImage {
+                    large
+                }
+            }
             }
         }
-        }";
+    }";
 
-    let recommendation: &str =  "
-        query ($genres: [String], $type: MediaType, $page: Int, $perPage: Int) {
-        Page(page: $page, perPage: $perPage) {
+    let season_chart: &str = "
+    query ($page: Int, $perPage: Int, $season: MediaSeason, $seasonYear: Int, $status: MediaStatus, $startedBefore: FuzzyDateInt, $endedAfter: FuzzyDateInt) {
+    Page (page: $page, perPage: $perPage) {
+        pageInfo {
+            hasNextPage
+        }
+        media (type: ANIME, season: $season, seasonYear: $seasonYear, status: $status, startDate_lesser: $startedBefore, endDate_greater: $endedAfter, format_in: [TV, TV_SHORT, MOVIE, ONA, OVA], sort: POPULARITY_DESC) {
+            id
+            format
+            status
+            episodes
+            genres
+            popularity
+            averageScore
+            meanScore
+            isAdult
+            siteUrl
+            title {
+                romaji
+                english
+                native
+            }
+            coverImage {
+                large
+            }
+            startDate {
+                year
+                month
+                day
+            }
+            nextAiringEpisode {
+                episode
+                airingAt
+            }
+            studios (isMain: true) {
+                nodes {
+                    name
+                }
+            }
+            }
+        }
+    }";
+
+    let rankings: &str = "
+    query ($page: Int, $perPage: Int, $type: MediaType, $sort: [MediaSort], $season: MediaSeason, $seasonYear: Int) {
+    Page (page: $page, perPage: $perPage) {
+        pageInfo {
+            total
+            currentPage
+            lastPage
+            hasNextPage
+        }
+        media (type: $type, sort: $sort, season: $season, seasonYear: $seasonYear, isAdult: false) {
+            id
+            format
+            status
+            episodes
+            chapters
+            genres
+            trending
+            popularity
+            averageScore
+            meanScore
+            siteUrl
+            title {
+                romaji
+                english
+                native
+            }
+            coverImage {
+                large
+            }
+            rankings {
+                rank
+                type
+                context
+                allTime
+                season
+                year
+                format
+            }
+            }
+        }
+    }";
+
+    let media_relations: &str = "
+    query ($id: Int) {
+    Media (id: $id) {
+        id
+        type
+        format
+        status
+        episodes
+        chapters
+        siteUrl
+        title {
+            romaji
+            english
+        }
+        startDate {
+            year
+            month
+            day
+        }
+        coverImage {
+            large
+        }
+        relations {
+            edges {
+                relationType(version: 2)
+                node {
+                    id
+                    type
+                }
+            }
+            }
+        }
+    }";
+
+    let reviews: &str = "
+    query ($id: Int, $page: Int, $perPage: Int, $sort: [ReviewSort], $withBody: Boolean!) {
+    Media (id: $id) {
+        id
+        reviews (page: $page, perPage: $perPage, sort: $sort) {
             pageInfo {
-                hasNextPage,
-                lastPage,
+                total
+                currentPage
+                lastPage
+                hasNextPage
             }
-            media(type: $type, genre_in: $genres, sort: ID) {
+            nodes {
                 id
+                summary
+                body(asHtml: true) @include(if: $withBody)
+                score
+                rating
+                ratingAmount
+                createdAt
+                siteUrl
+                user {
+                    id
+                    name
+                    siteUrl
+                    avatar {
+                        large
+                    }
+                }
             }
             }
-        }";
+        }
+    }";
+
+    let review_scores: &str = "
+    query ($id: Int, $page: Int) {
+    Media (id: $id) {
+        reviews (page: $page, perPage: 50) {
+            pageInfo {
+                total
+                hasNextPage
+            }
+            nodes {
+                score
+                rating
+                ratingAmount
+            }
+            }
+        }
+    }";
+
+    let media_batch: &str = "
+    query ($ids: [Int], $perPage: Int) {
+    Page (page: 1, perPage: $perPage) {
+        media (id_in: $ids) {
+            ...mediaFields
+            }
+        }
+    }";
+
+    let media_filter: &str = "
+    query ($page: Int, $perPage: Int, $search: String, $type: MediaType, $genres: [String], $excludedGenres: [String], $tags: [String], $excludedTags: [String], $minimumTagRank: Int, $formats: [MediaFormat], $status: MediaStatus, $season: MediaSeason, $startedAfter: FuzzyDateInt, $startedBefore: FuzzyDateInt, $scoreAbove: Int, $scoreBelow: Int, $popularityAbove: Int, $popularityBelow: Int, $country: CountryCode, $isAdult: Boolean, $sort: [MediaSort]) {
+    Page (page: $page, perPage: $perPage) {
+        pageInfo {
+            total
+            currentPage
+            lastPage
+            hasNextPage
+        }
+        media (search: $search, type: $type, genre_in: $genres, genre_not_in: $excludedGenres, tag_in: $tags, tag_not_in: $excludedTags, minimumTagRank: $minimumTagRank, format_in: $formats, status: $status, season: $season, startDate_greater: $startedAfter, startDate_lesser: $startedBefore, averageScore_greater: $scoreAbove, averageScore_lesser: $scoreBelow, popularity_greater: $popularityAbove, popularity_lesser: $popularityBelow, countryOfOrigin: $country, isAdult: $isAdult, sort: $sort) {
+            ...mediaFields
+            }
+        }
+    }";
+
+    let media_recommendations: &str = "
+    query ($id: Int, $page: Int, $perPage: Int) {
+    Media (id: $id) {
+        id
+        type
+        recommendations (page: $page, perPage: $perPage, sort: [RATING_DESC, ID]) {
+            pageInfo {
+                total
+                currentPage
+                lastPage
+                hasNextPage
+            }
+            nodes {
+                rating
+                mediaRecommendation {
+                    id
+                    type
+                    format
+                    status
+                    episodes
+                    chapters
+                    genres
+                    averageScore
+                    meanScore
+                    popularity
+                    siteUrl
+                    title {
+                        romaji
+                        english
+                        native
+                    }
+                    coverImage {
+                        large
+                    }
+                }
+            }
+            }
+        }
+    }";
+
+    let recommendation_candidates: &str = "
+    query ($page: Int, $perPage: Int, $type: MediaType, $genre: String) {
+    Page (page: $page, perPage: $perPage) {
+        pageInfo {
+            hasNextPage
+        }
+        media (type: $type, genre: $genre, status_not: NOT_YET_RELEASED, isAdult: false, sort: [POPULARITY_DESC]) {
+            id
+            type
+            format
+            genres
+            meanScore
+            popularity
+            siteUrl
+            title {
+                romaji
+                english
+                native
+            }
+            coverImage {
+                large
+            }
+            tags {
+                name
+                rank
+            }
+            }
+        }
+    }";
 
     match query_name {
-        "search" => search.to_string(),
+        "search" => format!("{}{}", search, MEDIA_FIELDS),
         "user_stats" => user_stats.to_string(),
         "relation_stats" => relation_stats.to_string(),
         "user" => user.to_string(),
         "affinity" => affinity.to_string(),
         "recommendation" => recommendation.to_string(),
-        "recommendation_amount" => recommendation_amount.to_string(),
+        "airing_warmup" => format!("{}{}", airing_warmup, MEDIA_FIELDS),
+        "characters" => characters.to_string(),
+        "staff" => staff.to_string(),
+        "studio_search" => studio_search.to_string(),
+        "studio" => studio.to_string(),
+        "airing_schedule" => airing_schedule.to_string(),
+        "season_chart" => season_chart.to_string(),
+        "rankings" => rankings.to_string(),
+        "media_relations" => media_relations.to_string(),
+        "reviews" => reviews.to_string(),
+        "review_scores" => review_scores.to_string(),
+        "media_batch" => format!("{}{}", media_batch, MEDIA_FIELDS),
+        "media_filter" => format!("{}{}", media_filter, MEDIA_FIELDS),
+        "media_recommendations" => media_recommendations.to_string(),
+        "recommendation_candidates" => recommendation_candidates.to_string(),
         _ => panic!("Invalid Query Name"),
     }
 }
\ No newline at end of file
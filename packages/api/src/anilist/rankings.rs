@@ -0,0 +1,114 @@
+use serde::Deserialize;
+use serde_json::json;
+use actix_web::{web, get, HttpResponse, Responder};
+use colourful_logger::Logger;
+use lazy_static::lazy_static;
+use crate::anilist::client::send_query;
+use crate::anilist::enums::{known_value, MEDIA_TYPES};
+use crate::cache::backend::{cached_json, store_json};
+use crate::cache::keys::RANKING;
+use crate::global::season::current_season;
+
+lazy_static! {
+    static ref logger: Logger = Logger::default();
+}
+
+#[derive(Deserialize)]
+struct RankingQuery {
+    media_type: Option<String>,
+    scope:      Option<String>,
+    page:       Option<i64>,
+    per_page:   Option<i64>,
+}
+
+#[get("/trending")]
+pub async fn trending(query: web::Query<RankingQuery>) -> impl Responder {
+    ranking("trending", "TRENDING_DESC", query.into_inner()).await
+}
+
+#[get("/popular")]
+pub async fn popular(query: web::Query<RankingQuery>) -> impl Responder {
+    ranking("popular", "POPULARITY_DESC", query.into_inner()).await
+}
+
+async fn ranking(kind: &str, sort: &str, query: RankingQuery) -> HttpResponse {
+    let Some(media_type) = known_value(query.media_type.as_deref().unwrap_or("ANIME"), &MEDIA_TYPES) else {
+        logger.error_single(&format!("Unknown media type : {:?}", query.media_type), "Rankings");
+        return HttpResponse::BadRequest().json(json!({"error": "media_type must be ANIME or MANGA"}));
+    };
+
+    let scope = query.scope.as_deref().unwrap_or("all").to_lowercase();
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(25).clamp(1, 50);
+
+    let mut variables = json!({"page": page, "perPage": per_page, "type": media_type, "sort": [sort]});
+    match scope.as_str() {
+        "all" => {},
+        "season" if media_type == "ANIME" => {
+            let (season, year) = current_season();
+            variables["season"] = season.into();
+            variables["seasonYear"] = year.into();
+        },
+        "season" => {
+            logger.error_single("Season scope requested for manga", "Rankings");
+            return HttpResponse::BadRequest().json(json!({"error": "The season scope is only available for anime"}));
+        },
+        _ => {
+            logger.error_single(&format!("Unknown ranking scope : {}", scope), "Rankings");
+            return HttpResponse::BadRequest().json(json!({"error": "scope must be all or season"}));
+        }
+    }
+
+    let redis_key = format!("{}:{}:{}:{}:{}:{}", RANKING, kind, media_type.to_lowercase(), scope, page, per_page);
+    if let Some(ranking) = cached_json(&redis_key) {
+        logger.debug_single(&format!("Found {} {} ranking in cache", scope, kind), "Rankings");
+        return HttpResponse::Ok().json(ranking);
+    }
+
+    let ranking = match send_query("rankings", variables, "Rankings").await {
+        Ok(ranking) => wash_ranking(kind, &scope, page, per_page, ranking),
+        Err(response) => return response,
+    };
+
+    store_json(&redis_key, &ranking, 1800);
+    HttpResponse::Ok().json(ranking)
+}
+
+fn wash_ranking(kind: &str, scope: &str, page: i64, per_page: i64, ranking: serde_json::Value) -> serde_json::Value {
+    logger.debug_single("Washing up ranking data", "Rankings");
+    let data = &ranking["data"]["Page"];
+
+    let media: Vec<serde_json::Value> = data["media"]
+        .as_array()
+        .unwrap_or(&vec![])
+        .iter()
+        .enumerate()
+        .map(|(index, media)| json!({
+            "rank"          : (page - 1) * per_page + index as i64 + 1,
+            "id"            : media["id"],
+            "romaji"        : media["title"]["romaji"],
+            "english"       : media["title"]["english"],
+            "native"        : media["title"]["native"],
+            "format"        : media["format"],
+            "status"        : media["status"],
+            "episodes"      : media["episodes"],
+            "chapters"      : media["chapters"],
+            "genres"        : media["genres"],
+            "trending"      : media["trending"],
+            "popularity"    : media["popularity"],
+            "averageScore"  : media["averageScore"],
+            "meanScore"     : media["meanScore"],
+            "cover"         : media["coverImage"]["large"],
+            "url"           : media["siteUrl"],
+            "rankings"      : media["rankings"],
+        }))
+        .collect();
+
+    json!({
+        "ranking"   : kind,
+        "scope"     : scope,
+        "pageInfo"  : data["pageInfo"],
+        "media"     : media,
+        "dataFrom"  : "API",
+    })
+}
@@ -0,0 +1,335 @@
+use std::collections::{HashMap, HashSet};
+use serde::Deserialize;
+use serde_json::json;
+use actix_web::{web, get, HttpResponse, Responder};
+use colourful_logger::Logger;
+use lazy_static::lazy_static;
+use crate::anilist::client::send_query;
+use crate::anilist::enums::{known_value, MEDIA_TYPES};
+use crate::anilist::lists::{fetch_media_list, list_key, listed_ids};
+use crate::anilist::media::media_ttl;
+use crate::cache::backend::{cached_json, store_json};
+use crate::cache::keys::media_key;
+use crate::global::rate_budget::wait_for_budget;
+
+lazy_static! {
+    static ref logger: Logger = Logger::default();
+}
+
+#[derive(Deserialize)]
+struct RecommendationQuery {
+    page:           Option<i64>,
+    per_page:       Option<i64>,
+    exclude_user:   Option<String>,
+}
+
+const CANDIDATE_PAGES: i64 = 3;
+const GENRE_CANDIDATES: usize = 30;
+const PROFILE_GENRES: usize = 3;
+const MIN_TAG_RANK: f64 = 60.0;
+
+#[derive(Deserialize)]
+struct UserRecommendationQuery {
+    media_type: Option<String>,
+    limit:      Option<usize>,
+}
+
+#[get("/media/{id}/recommendations")]
+pub async fn media_recommendations(path: web::Path<i64>, query: web::Query<RecommendationQuery>) -> impl Responder {
+    let media_id = path.into_inner();
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(25).clamp(1, 50);
+
+    let redis_key = format!("{}:recommendations:{}:{}", media_key(media_id), page, per_page);
+    let mut recommendations = match cached_json(&redis_key) {
+        Some(recommendations) => {
+            logger.debug_single(&format!("Found recommendations for {} in cache", media_id), "Recommendations");
+            recommendations
+        },
+        None => {
+            let variables = json!({"id": media_id, "page": page, "perPage": per_page});
+            let recommendations = match send_query("media_recommendations", variables, "Recommendations").await {
+                Ok(recommendations) => wash_recommendations(recommendations),
+                Err(response) => return response,
+            };
+
+            store_json(&redis_key, &recommendations, media_ttl(media_id));
+            recommendations
+        }
+    };
+
+    if let Some(username) = query.exclude_user.as_deref().filter(|username| !username.trim().is_empty()) {
+        let media_type = recommendations["mediaType"].as_str().unwrap_or("ANIME").to_string();
+        let listed = match fetch_media_list(username.trim(), &media_type).await {
+            Ok(list) => listed_ids(&list),
+            Err(response) => return response,
+        };
+
+        let entries = recommendations["recommendations"].as_array_mut().unwrap();
+        let before = entries.len();
+        entries.retain(|entry| !entry["id"].as_i64().is_some_and(|id| listed.contains(&id)));
+        let excluded = before - entries.len();
+        recommendations["excluded"] = excluded.into();
+    }
+
+    HttpResponse::Ok().json(recommendations)
+}
+
+fn wash_recommendations(recommendations: serde_json::Value) -> serde_json::Value {
+    logger.debug_single("Washing up recommendation data", "Recommendations");
+    let data = &recommendations["data"]["Media"];
+    let mut washed_recommendations = Vec::new();
+
+    for node in data["recommendations"]["nodes"].as_array().unwrap_or(&vec![]) {
+        // Recommendations of deleted media are still returned, without the media
+        let media = &node["mediaRecommendation"];
+        if media.is_null() {
+            continue;
+        }
+
+        washed_recommendations.push(json!({
+            "id"            : media["id"],
+            "rating"        : node["rating"],
+            "romaji"        : media["title"]["romaji"],
+            "english"       : media["title"]["english"],
+            "native"        : media["title"]["native"],
+            "type"          : media["type"],
+            "format"        : media["format"],
+            "status"        : media["status"],
+            "episodes"      : media["episodes"],
+            "chapters"      : media["chapters"],
+            "genres"        : media["genres"],
+            "averageScore"  : media["averageScore"],
+            "meanScore"     : media["meanScore"],
+            "popularity"    : media["popularity"],
+            "cover"         : media["coverImage"]["large"],
+            "url"           : media["siteUrl"],
+        }));
+    }
+
+    json!({
+        "mediaId"           : data["id"],
+        "mediaType"         : data["type"],
+        "pageInfo"          : data["recommendations"]["pageInfo"],
+        "recommendations"   : washed_recommendations,
+        "dataFrom"          : "API",
+    })
+}
+
+#[get("/user/{name}/recommend")]
+pub async fn user_recommendations(path: web::Path<String>, query: web::Query<UserRecommendationQuery>) -> impl Responder {
+    let username = path.into_inner();
+    let Some(media_type) = known_value(query.media_type.as_deref().unwrap_or("ANIME"), &MEDIA_TYPES) else {
+        logger.error_single(&format!("Unknown media type : {:?}", query.media_type), "Recommendations");
+        return HttpResponse::BadRequest().json(json!({"error": "media_type must be ANIME or MANGA"}));
+    };
+
+    let limit = query.limit.unwrap_or(10).clamp(1, 50);
+    let redis_key = format!("{}:recommend:{}", list_key(&username, media_type), limit);
+    if let Some(recommendations) = cached_json(&redis_key) {
+        logger.debug_single(&format!("Found recommendations for {} in cache", username), "Recommendations");
+        return HttpResponse::Ok().json(recommendations);
+    }
+
+    let list = match fetch_media_list(&username, media_type).await {
+        Ok(list) => list,
+        Err(response) => return response,
+    };
+
+    let profile = build_profile(&list);
+    let genres: Vec<&String> = profile.top_genres();
+    if genres.is_empty() {
+        logger.warn_single(&format!("{} has no list entries to build a profile from", username), "Recommendations");
+        return HttpResponse::NotFound().json(json!({"error": "Not enough list entries to recommend from", "errorCode": 404}));
+    }
+
+    // Anilist only returns media with every genre given, so each top genre is queried on its own and the results merged.
+    // Listed media are filtered out here rather than in the query, as sending a whole list would make the query huge
+    let listed = listed_ids(&list);
+    let mut seen: HashSet<i64> = HashSet::new();
+    let mut candidates: Vec<serde_json::Value> = Vec::new();
+    for genre in genres {
+        let mut found = 0;
+        for page in 1..=CANDIDATE_PAGES {
+            wait_for_budget().await;
+            let variables = json!({"page": page, "perPage": 50, "type": media_type, "genre": genre});
+            let data = match send_query("recommendation_candidates", variables, "Recommendations").await {
+                Ok(data) => data,
+                Err(response) => return response,
+            };
+
+            let data = &data["data"]["Page"];
+            for media in data["media"].as_array().unwrap_or(&vec![]) {
+                let Some(media_id) = media["id"].as_i64() else {
+                    continue;
+                };
+                if !listed.contains(&media_id) && seen.insert(media_id) {
+                    candidates.push(media.clone());
+                    found += 1;
+                }
+            }
+
+            // Users with long lists have most popular media listed already, so more pages are fetched until enough remain
+            if found >= GENRE_CANDIDATES || !data["pageInfo"]["hasNextPage"].as_bool().unwrap_or(false) {
+                break;
+            }
+        }
+    }
+
+    let matches: Vec<(f64, Vec<serde_json::Value>)> = candidates.iter().map(|media| profile.score(media)).collect();
+    let best_match = matches.iter().map(|(total, _)| *total).fold(f64::EPSILON, f64::max);
+    let most_popular = candidates.iter().filter_map(|media| media["popularity"].as_f64()).fold(1.0, f64::max);
+
+    let mut ranked: Vec<(f64, serde_json::Value)> = candidates
+        .iter()
+        .zip(matches)
+        .map(|(media, (total, reasons))| {
+            let profile_match = total.max(0.0) / best_match;
+            let popularity = (media["popularity"].as_f64().unwrap_or(0.0) + 1.0).ln() / (most_popular + 1.0).ln();
+            let mean_score = media["meanScore"].as_f64().unwrap_or(0.0) / 100.0;
+            let score = profile_match * 0.6 + popularity * 0.2 + mean_score * 0.2;
+            (score, wash_candidate(media, score, profile_match, reasons))
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let recommendations: Vec<serde_json::Value> = ranked.into_iter().take(limit).map(|(_, media)| media).collect();
+
+    let recommendations = json!({
+        "user"              : list["user"],
+        "mediaType"         : media_type,
+        "profile"           : profile.summary(),
+        "recommendations"   : recommendations,
+        "dataFrom"          : "API",
+    });
+
+    store_json(&redis_key, &recommendations, 1800);
+    HttpResponse::Ok().json(recommendations)
+}
+
+/// How much a user leans towards each genre and tag, positive for what they rate above their own mean.
+struct Profile {
+    genres: HashMap<String, f64>,
+    tags:   HashMap<String, f64>,
+}
+
+impl Profile {
+    fn top_genres(&self) -> Vec<&String> {
+        let mut genres: Vec<(&String, &f64)> = self.genres.iter().filter(|(_, weight)| **weight > 0.0).collect();
+        genres.sort_by(|a, b| b.1.total_cmp(a.1));
+        genres.into_iter().take(PROFILE_GENRES).map(|(genre, _)| genre).collect()
+    }
+
+    /// Sums how much a media's genres and tags match the profile, with the ones contributing most.
+    fn score(&self, media: &serde_json::Value) -> (f64, Vec<serde_json::Value>) {
+        let mut contributions: Vec<(String, &str, f64)> = Vec::new();
+
+        for genre in media["genres"].as_array().unwrap_or(&vec![]).iter().filter_map(|genre| genre.as_str()) {
+            if let Some(weight) = self.genres.get(genre) {
+                contributions.push((genre.to_string(), "genre", *weight));
+            }
+        }
+
+        for tag in media["tags"].as_array().unwrap_or(&vec![]) {
+            let (Some(name), Some(rank)) = (tag["name"].as_str(), tag["rank"].as_f64()) else {
+                continue;
+            };
+            if let Some(weight) = self.tags.get(name).filter(|_| rank >= MIN_TAG_RANK) {
+                contributions.push((name.to_string(), "tag", weight * rank / 100.0));
+            }
+        }
+
+        let total: f64 = contributions.iter().map(|(_, _, weight)| weight).sum();
+        contributions.sort_by(|a, b| b.2.total_cmp(&a.2));
+        let reasons = contributions
+            .iter()
+            .filter(|(_, _, weight)| *weight > 0.0)
+            .take(3)
+            .map(|(name, kind, weight)| json!({"name": name, "kind": kind, "weight": (weight * 100.0).round() / 100.0}))
+            .collect();
+
+        (total, reasons)
+    }
+
+    fn summary(&self) -> serde_json::Value {
+        let top = |weights: &HashMap<String, f64>| -> Vec<serde_json::Value> {
+            let mut weights: Vec<(&String, &f64)> = weights.iter().collect();
+            weights.sort_by(|a, b| b.1.total_cmp(a.1));
+            weights.into_iter().take(5).map(|(name, weight)| json!({"name": name, "weight": (weight * 100.0).round() / 100.0})).collect()
+        };
+
+        json!({"genres": top(&self.genres), "tags": top(&self.tags)})
+    }
+}
+
+fn entry_weight(entry: &serde_json::Value, mean_score: Option<f64>) -> Option<f64> {
+    match (entry["score"].as_f64().filter(|score| *score > 0.0), mean_score) {
+        (Some(score), Some(mean_score)) => Some((score - mean_score) / 10.0 + 1.0),
+        _ => match entry["status"].as_str() {
+            Some("COMPLETED") | Some("CURRENT") | Some("REPEATING") => Some(1.0),
+            Some("DROPPED") => Some(-2.0),
+            _ => None,
+        },
+    }
+}
+
+/// Weights each list entry by how the user scored it against their own mean, unscored entries count by status.
+fn build_profile(list: &serde_json::Value) -> Profile {
+    let entries = list["entries"].as_array().cloned().unwrap_or_default();
+    let scores: Vec<f64> = entries.iter().filter_map(|entry| entry["score"].as_f64()).filter(|score| *score > 0.0).collect();
+    let mean_score = (!scores.is_empty()).then(|| scores.iter().sum::<f64>() / scores.len() as f64);
+
+    let mut genres: HashMap<String, (f64, f64)> = HashMap::new();
+    let mut tags: HashMap<String, (f64, f64)> = HashMap::new();
+
+    for entry in &entries {
+        let Some(weight) = entry_weight(entry, mean_score) else {
+            continue;
+        };
+
+        for genre in entry["genres"].as_array().unwrap_or(&vec![]).iter().filter_map(|genre| genre.as_str()) {
+            let total = genres.entry(genre.to_string()).or_default();
+            total.0 += weight;
+            total.1 += 1.0;
+        }
+
+        for tag in entry["tags"].as_array().unwrap_or(&vec![]) {
+            let (Some(name), Some(rank)) = (tag["name"].as_str(), tag["rank"].as_f64()) else {
+                continue;
+            };
+            if rank >= MIN_TAG_RANK {
+                let total = tags.entry(name.to_string()).or_default();
+                total.0 += weight * rank / 100.0;
+                total.1 += 1.0;
+            }
+        }
+    }
+
+    // Damped averages, so a genre seen once does not outweigh one seen across the whole list
+    let average = |totals: HashMap<String, (f64, f64)>| -> HashMap<String, f64> {
+        totals.into_iter().map(|(name, (weight, count))| (name, weight / (count + 3.0) * count.ln_1p())).collect()
+    };
+
+    Profile {
+        genres: average(genres),
+        tags:   average(tags),
+    }
+}
+
+fn wash_candidate(media: &serde_json::Value, score: f64, profile_match: f64, reasons: Vec<serde_json::Value>) -> serde_json::Value {
+    json!({
+        "id"            : media["id"],
+        "romaji"        : media["title"]["romaji"],
+        "english"       : media["title"]["english"],
+        "native"        : media["title"]["native"],
+        "format"        : media["format"],
+        "genres"        : media["genres"],
+        "meanScore"     : media["meanScore"],
+        "popularity"    : media["popularity"],
+        "cover"         : media["coverImage"]["large"],
+        "url"           : media["siteUrl"],
+        "score"         : (score * 1000.0).round() / 1000.0,
+        "profileMatch"  : (profile_match * 1000.0).round() / 1000.0,
+        "because"       : reasons,
+    })
+}
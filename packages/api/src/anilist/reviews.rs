@@ -0,0 +1,159 @@
+use serde::Deserialize;
+use serde_json::json;
+use actix_web::{web, get, HttpResponse, Responder};
+use colourful_logger::Logger;
+use lazy_static::lazy_static;
+use crate::anilist::client::send_query;
+use crate::anilist::media::media_ttl;
+use crate::cache::backend::{cached_json, store_json};
+use crate::cache::keys::media_key;
+use crate::global::markdown::{html_to_markdown, Spoilers};
+use crate::global::rate_budget::wait_for_budget;
+
+lazy_static! {
+    static ref logger: Logger = Logger::default();
+}
+
+const REVIEW_SORTS: [(&str, &str); 3] = [("rating", "RATING_DESC"), ("score", "SCORE_DESC"), ("recent", "CREATED_AT_DESC")];
+const MAX_STAT_PAGES: i64 = 10;
+
+#[derive(Deserialize)]
+struct ReviewQuery {
+    page:       Option<i64>,
+    per_page:   Option<i64>,
+    sort:       Option<String>,
+    full:       Option<bool>,
+}
+
+#[get("/media/{id}/reviews")]
+pub async fn media_reviews(path: web::Path<i64>, query: web::Query<ReviewQuery>) -> impl Responder {
+    let media_id = path.into_inner();
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(10).clamp(1, 25);
+    let full = query.full.unwrap_or(false);
+
+    let sort_name = query.sort.as_deref().unwrap_or("rating").to_lowercase();
+    let Some((_, sort)) = REVIEW_SORTS.iter().find(|(name, _)| *name == sort_name) else {
+        logger.error_single(&format!("Unknown review sort : {}", sort_name), "Reviews");
+        return HttpResponse::BadRequest().json(json!({"error": "sort must be rating, score or recent"}));
+    };
+
+    let redis_key = format!("{}:reviews:{}:{}:{}:{}", media_key(media_id), sort_name, page, per_page, full);
+    let mut reviews = match cached_json(&redis_key) {
+        Some(reviews) => {
+            logger.debug_single(&format!("Found reviews for {} in cache", media_id), "Reviews");
+            reviews
+        },
+        None => {
+            let variables = json!({"id": media_id, "page": page, "perPage": per_page, "sort": [sort], "withBody": full});
+            let reviews = match send_query("reviews", variables, "Reviews").await {
+                Ok(reviews) => wash_reviews(reviews, full),
+                Err(response) => return response,
+            };
+
+            store_json(&redis_key, &reviews, media_ttl(media_id));
+            reviews
+        }
+    };
+
+    reviews["stats"] = json!(review_stats(media_id).await);
+
+    HttpResponse::Ok().json(reviews)
+}
+
+/// Aggregates are taken over every review (up to 500), independent of the requested page and sort.
+/// `None` when a page could not be fetched, so the reviews are still returned without them.
+async fn review_stats(media_id: i64) -> Option<serde_json::Value> {
+    let redis_key = format!("{}:reviews:stats", media_key(media_id));
+    if let Some(mut stats) = cached_json(&redis_key) {
+        let object = stats.as_object_mut().unwrap();
+        object.remove("dataFrom");
+        object.remove("leftUntilExpire");
+        return Some(stats);
+    }
+
+    let mut scores: Vec<i64> = Vec::new();
+    let mut upvotes = 0;
+    let mut votes = 0;
+    let mut total = 0;
+
+    for page in 1..=MAX_STAT_PAGES {
+        wait_for_budget().await;
+        let Ok(data) = send_query("review_scores", json!({"id": media_id, "page": page}), "Reviews").await else {
+            logger.warn_single(&format!("Unable to fetch review page {} of {}, leaving out stats", page, media_id), "Reviews");
+            return None;
+        };
+        let reviews = &data["data"]["Media"]["reviews"];
+        total = reviews["pageInfo"]["total"].as_i64().unwrap_or(0);
+
+        for review in reviews["nodes"].as_array().unwrap_or(&vec![]) {
+            if let Some(score) = review["score"].as_i64() {
+                scores.push(score);
+            }
+            upvotes += review["rating"].as_i64().unwrap_or(0);
+            votes += review["ratingAmount"].as_i64().unwrap_or(0);
+        }
+
+        if !reviews["pageInfo"]["hasNextPage"].as_bool().unwrap_or(false) {
+            break;
+        }
+    }
+
+    let mean_score = match scores.len() {
+        0 => None,
+        count => Some((scores.iter().sum::<i64>() as f64 / count as f64 * 10.0).round() / 10.0),
+    };
+    let approval = match votes {
+        0 => None,
+        votes => Some((upvotes as f64 / votes as f64 * 1000.0).round() / 10.0),
+    };
+
+    let stats = json!({
+        "count"         : total,
+        "sampled"       : scores.len(),
+        "meanScore"     : mean_score,
+        "highestScore"  : scores.iter().max(),
+        "lowestScore"   : scores.iter().min(),
+        "approval"      : approval,
+    });
+
+    store_json(&redis_key, &stats, media_ttl(media_id));
+    Some(stats)
+}
+
+fn wash_reviews(reviews: serde_json::Value, full: bool) -> serde_json::Value {
+    logger.debug_single("Washing up review data", "Reviews");
+    let data = &reviews["data"]["Media"]["reviews"];
+    let mut washed_reviews = Vec::new();
+
+    for review in data["nodes"].as_array().unwrap_or(&vec![]) {
+        let mut washed = json!({
+            "id"            : review["id"],
+            "summary"       : review["summary"],
+            "score"         : review["score"],
+            "rating"        : review["rating"],
+            "ratingAmount"  : review["ratingAmount"],
+            "createdAt"     : review["createdAt"],
+            "url"           : review["siteUrl"],
+            "author"        : {
+                "id"        : review["user"]["id"],
+                "name"      : review["user"]["name"],
+                "avatar"    : review["user"]["avatar"]["large"],
+                "url"       : review["user"]["siteUrl"],
+            },
+        });
+
+        if full {
+            washed["body"] = review["body"].as_str().map(|body| html_to_markdown(body, Spoilers::Wrap)).into();
+        }
+
+        washed_reviews.push(washed);
+    }
+
+    json!({
+        "mediaId"   : reviews["data"]["Media"]["id"],
+        "pageInfo"  : data["pageInfo"],
+        "reviews"   : washed_reviews,
+        "dataFrom"  : "API",
+    })
+}
@@ -0,0 +1,156 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use serde_json::json;
+use actix_web::{web, get, HttpResponse, Responder};
+use colourful_logger::Logger;
+use lazy_static::lazy_static;
+use crate::anilist::client::send_query;
+use crate::cache::backend::{cached_json, store_json};
+use crate::cache::keys::SCHEDULE;
+
+lazy_static! {
+    static ref logger: Logger = Logger::default();
+}
+
+const MAX_PAGES: i64 = 10;
+const MAX_RANGE: i64 = 14 * 86400;
+
+#[derive(Deserialize)]
+struct AiringQuery {
+    window:         Option<String>,
+    start:          Option<i64>,
+    end:            Option<i64>,
+    timezone:       Option<String>,
+    include_adult:  Option<bool>,
+}
+
+fn now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64
+}
+
+/// Works out the unix time range to fetch, days and weeks start at midnight in the requested timezone.
+fn airing_window(query: &AiringQuery, timezone: Tz) -> Result<(i64, i64), String> {
+    let window = query.window.as_deref().unwrap_or(if query.start.is_some() { "range" } else { "day" });
+
+    match window.to_lowercase().as_str() {
+        "day" | "week" => {
+            let today = Utc::now().with_timezone(&timezone).date_naive();
+            let midnight = today.and_hms_opt(0, 0, 0).unwrap();
+            let start = timezone.from_local_datetime(&midnight).earliest().map_or(now(), |start| start.timestamp());
+            let days = if window.eq_ignore_ascii_case("week") { 7 } else { 1 };
+            Ok((start, start + Duration::days(days).num_seconds()))
+        },
+        "range" => match (query.start, query.end) {
+            (Some(start), Some(end)) if end > start && end - start <= MAX_RANGE => Ok((start, end)),
+            (Some(_), Some(_)) => Err(format!("end must be after start and within {} days of it", MAX_RANGE / 86400)),
+            _ => Err("start and end are required for a range".to_string()),
+        },
+        _ => Err("window must be day, week or range".to_string()),
+    }
+}
+
+#[get("/airing")]
+pub async fn airing_schedule(query: web::Query<AiringQuery>) -> impl Responder {
+    let timezone: Tz = match query.timezone.as_deref().unwrap_or("UTC").parse() {
+        Ok(timezone) => timezone,
+        Err(_) => {
+            logger.error_single(&format!("Unknown timezone : {:?}", query.timezone), "Airing");
+            return HttpResponse::BadRequest().json(json!({"error": "Unknown IANA timezone"}));
+        }
+    };
+
+    let (start, end) = match airing_window(&query, timezone) {
+        Ok(window) => window,
+        Err(e) => {
+            logger.error_single(&e, "Airing");
+            return HttpResponse::BadRequest().json(json!({"error": e}));
+        }
+    };
+
+    let redis_key = format!("{}:{}:{}", SCHEDULE, start, end);
+    let mut schedule = match cached_json(&redis_key) {
+        Some(schedule) => {
+            logger.debug_single(&format!("Found airing schedule {} - {} in cache", start, end), "Airing");
+            schedule
+        },
+        None => {
+            let schedule = match fetch_schedule(start, end).await {
+                Ok(schedule) => schedule,
+                Err(response) => return response,
+            };
+
+            // Keep the schedule until the next episode within it airs
+            let ttl = schedule["episodes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter_map(|episode| episode["airingAt"].as_i64())
+                .find(|airing_at| *airing_at > now())
+                .map_or(3600, |airing_at| airing_at - now())
+                .max(60);
+
+            store_json(&redis_key, &schedule, ttl);
+            schedule
+        }
+    };
+
+    let include_adult = query.include_adult.unwrap_or(false);
+    let episodes: Vec<serde_json::Value> = schedule["episodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|episode| include_adult || !episode["isAdult"].as_bool().unwrap_or(false))
+        .map(|episode| {
+            let mut episode = episode.clone();
+            if let Some(airing_at) = episode["airingAt"].as_i64().and_then(|airing_at| DateTime::from_timestamp(airing_at, 0)) {
+                episode["localTime"] = airing_at.with_timezone(&timezone).to_rfc3339().into();
+            }
+            episode
+        })
+        .collect();
+
+    schedule["timezone"] = timezone.name().into();
+    schedule["episodes"] = episodes.into();
+    HttpResponse::Ok().json(schedule)
+}
+
+async fn fetch_schedule(start: i64, end: i64) -> Result<serde_json::Value, HttpResponse> {
+    let mut episodes: Vec<serde_json::Value> = Vec::new();
+
+    for page in 1..=MAX_PAGES {
+        let variables = json!({"page": page, "perPage": 50, "start": start - 1, "end": end});
+        let schedule = send_query("airing_schedule", variables, "Airing").await?;
+        let data = &schedule["data"]["Page"];
+
+        for episode in data["airingSchedules"].as_array().unwrap_or(&vec![]) {
+            let media = &episode["media"];
+            episodes.push(json!({
+                "mediaId"   : media["id"],
+                "romaji"    : media["title"]["romaji"],
+                "english"   : media["title"]["english"],
+                "native"    : media["title"]["native"],
+                "episode"   : episode["episode"],
+                "airingAt"  : episode["airingAt"],
+                "format"    : media["format"],
+                "isAdult"   : media["isAdult"],
+                "popularity": media["popularity"],
+                "cover"     : media["coverImage"]["large"],
+                "url"       : media["siteUrl"],
+            }));
+        }
+
+        if !data["pageInfo"]["hasNextPage"].as_bool().unwrap_or(false) {
+            break;
+        }
+    }
+
+    logger.debug_single(&format!("Found {} episodes airing between {} and {}", episodes.len(), start, end), "Airing");
+    Ok(json!({
+        "start"     : start,
+        "end"       : end,
+        "episodes"  : episodes,
+        "dataFrom"  : "API",
+    }))
+}
@@ -0,0 +1,199 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use serde::Deserialize;
+use serde_json::json;
+use actix_web::{web, post, HttpResponse, Responder};
+use colourful_logger::Logger;
+use lazy_static::lazy_static;
+use crate::anilist::client::send_query;
+use crate::anilist::enums::{known_value, COUNTRIES, GENRES, MEDIA_FORMATS, MEDIA_SORTS, MEDIA_STATUSES, MEDIA_TYPES};
+use crate::anilist::media::{cache_media, set_display_title, title_language, wash_media_node};
+use crate::cache::backend::{cached_json, store_json};
+use crate::cache::keys::SEARCH;
+use crate::global::season::SEASONS;
+
+lazy_static! {
+    static ref logger: Logger = Logger::default();
+}
+
+/// Filters shared by the media search and random recommendations.
+#[derive(Deserialize)]
+pub struct MediaFilters {
+    genres:         Option<Vec<String>>,
+    exclude_genres: Option<Vec<String>>,
+    tags:           Option<Vec<String>>,
+    exclude_tags:   Option<Vec<String>>,
+    formats:        Option<Vec<String>>,
+    year_from:      Option<i64>,
+    year_to:        Option<i64>,
+    adult:          Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct SearchRequest {
+    search:         Option<String>,
+    media_type:     Option<String>,
+    #[serde(flatten)]
+    filters:        MediaFilters,
+    min_tag_rank:   Option<i64>,
+    status:         Option<String>,
+    season:         Option<String>,
+    score_min:      Option<i64>,
+    score_max:      Option<i64>,
+    popularity_min: Option<i64>,
+    popularity_max: Option<i64>,
+    country:        Option<String>,
+    sort:           Option<String>,
+    page:           Option<i64>,
+    per_page:       Option<i64>,
+    title_language: Option<String>,
+}
+
+fn invalid(field: &str, value: &str, known: &[&str]) -> serde_json::Value {
+    logger.error_single(&format!("Unknown {} : {}", field, value), "Search");
+    json!({"error": format!("Unknown {}: {}", field, value), "accepted": known})
+}
+
+fn known_values(field: &str, values: &Option<Vec<String>>, known: &[&'static str]) -> Result<Option<Vec<&'static str>>, serde_json::Value> {
+    let Some(values) = values.as_ref().filter(|values| !values.is_empty()) else {
+        return Ok(None);
+    };
+
+    values
+        .iter()
+        .map(|value| known_value(value, known).ok_or_else(|| invalid(field, value, known)))
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+fn known_single(field: &str, value: &Option<String>, known: &[&'static str]) -> Result<Option<&'static str>, serde_json::Value> {
+    match value {
+        Some(value) => known_value(value, known).map(Some).ok_or_else(|| invalid(field, value, known)),
+        None => Ok(None),
+    }
+}
+
+/// Tags are not validated against a fixed list as Anilist adds new ones regularly, only trimmed and de-duplicated.
+fn tag_values(tags: &Option<Vec<String>>) -> Option<Vec<String>> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for tag in tags.as_ref()? {
+        let tag = tag.trim().to_string();
+        if !tag.is_empty() && !cleaned.contains(&tag) {
+            cleaned.push(tag);
+        }
+    }
+    (!cleaned.is_empty()).then_some(cleaned)
+}
+
+impl MediaFilters {
+    /// Validates the filters and adds them to `variables`, returning the error body for a bad request otherwise.
+    pub fn apply(&self, mut variables: serde_json::Value) -> Result<serde_json::Value, serde_json::Value> {
+        if let (Some(year_from), Some(year_to)) = (self.year_from, self.year_to) {
+            if year_from > year_to {
+                return Err(json!({"error": "year_from must not be after year_to"}));
+            }
+        }
+
+        variables["genres"] = json!(known_values("genre", &self.genres, &GENRES)?);
+        variables["excludedGenres"] = json!(known_values("genre", &self.exclude_genres, &GENRES)?);
+        variables["tags"] = json!(tag_values(&self.tags));
+        variables["excludedTags"] = json!(tag_values(&self.exclude_tags));
+        variables["formats"] = json!(known_values("format", &self.formats, &MEDIA_FORMATS)?);
+        // Anilist range filters are exclusive, so every bound is widened by one to make them inclusive.
+        // Callers widen the score and popularity bounds they add the same way
+        variables["startedAfter"] = json!(self.year_from.map(|year| (year - 1) * 10000 + 1231));
+        variables["startedBefore"] = json!(self.year_to.map(|year| (year + 1) * 10000));
+        variables["isAdult"] = self.adult.unwrap_or(false).into();
+        Ok(variables)
+    }
+}
+
+/// Validates the filters and builds the query variables, returning the error body for a bad request otherwise.
+fn build_variables(req: &SearchRequest) -> Result<serde_json::Value, serde_json::Value> {
+    let media_type = known_single("media type", &req.media_type, &MEDIA_TYPES)?.unwrap_or("ANIME");
+    let search = req.search.as_deref().map(str::trim).filter(|search| !search.is_empty());
+    let default_sort = if search.is_some() { "SEARCH_MATCH" } else { "POPULARITY_DESC" };
+    let sort = known_single("sort", &req.sort, &MEDIA_SORTS)?.unwrap_or(default_sort);
+
+    for (name, value) in [("score_min", req.score_min), ("score_max", req.score_max)] {
+        if value.is_some_and(|score| !(0..=100).contains(&score)) {
+            return Err(json!({"error": format!("{} must be between 0 and 100", name)}));
+        }
+    }
+
+    if req.min_tag_rank.is_some_and(|rank| !(0..=100).contains(&rank)) {
+        return Err(json!({"error": "min_tag_rank must be between 0 and 100"}));
+    }
+
+    req.filters.apply(json!({
+        "page"              : req.page.unwrap_or(1).max(1),
+        "perPage"           : req.per_page.unwrap_or(25).clamp(1, 50),
+        "search"            : search,
+        "type"              : media_type,
+        "minimumTagRank"    : req.min_tag_rank,
+        "status"            : known_single("status", &req.status, &MEDIA_STATUSES)?,
+        "season"            : known_single("season", &req.season, &SEASONS)?,
+        "scoreAbove"        : req.score_min.map(|score| score - 1),
+        "scoreBelow"        : req.score_max.map(|score| score + 1),
+        "popularityAbove"   : req.popularity_min.map(|popularity| popularity - 1),
+        "popularityBelow"   : req.popularity_max.map(|popularity| popularity + 1),
+        "country"           : known_single("country", &req.country, &COUNTRIES)?,
+        "sort"              : [sort],
+    }))
+}
+
+#[post("/search/media")]
+pub async fn media_filter_search(req: web::Json<SearchRequest>) -> impl Responder {
+    let variables = match build_variables(&req) {
+        Ok(variables) => variables,
+        Err(error) => return HttpResponse::BadRequest().json(error),
+    };
+
+    let language = match req.title_language.as_deref().map(title_language).transpose() {
+        Ok(language) => language,
+        Err(error) => return HttpResponse::BadRequest().json(error),
+    };
+
+    let mut hasher = DefaultHasher::new();
+    variables.to_string().hash(&mut hasher);
+    let redis_key = format!("{}:{:x}", SEARCH, hasher.finish());
+
+    if let Some(mut results) = cached_json(&redis_key) {
+        logger.debug_single("Found search results in cache", "Search");
+        apply_title_language(&mut results, language);
+        return HttpResponse::Ok().json(results);
+    }
+
+    let results = match send_query("media_filter", variables, "Search").await {
+        Ok(results) => results,
+        Err(response) => return response,
+    };
+
+    let data = &results["data"]["Page"];
+    let mut media: Vec<serde_json::Value> = Vec::new();
+    for node in data["media"].as_array().unwrap_or(&vec![]) {
+        let washed = wash_media_node(node.clone()).await;
+        cache_media(&washed);
+        media.push(washed);
+    }
+
+    let mut results = json!({
+        "pageInfo"  : data["pageInfo"],
+        "media"     : media,
+        "dataFrom"  : "API",
+    });
+
+    store_json(&redis_key, &results, 1800);
+    apply_title_language(&mut results, language);
+    HttpResponse::Ok().json(results)
+}
+
+fn apply_title_language(results: &mut serde_json::Value, language: Option<&str>) {
+    let (Some(language), Some(media)) = (language, results["media"].as_array_mut()) else {
+        return;
+    };
+
+    for media in media {
+        set_display_title(media, language);
+    }
+}
@@ -0,0 +1,177 @@
+use std::cmp::Ordering;
+use serde::Deserialize;
+use serde_json::json;
+use actix_web::{web, get, HttpResponse, Responder};
+use colourful_logger::Logger;
+use lazy_static::lazy_static;
+use crate::anilist::client::send_query;
+use crate::cache::backend::{cached_json, store_json};
+use crate::cache::keys::SEASON;
+use crate::global::season::{current_season, season_index, season_start, sortable_date, SEASONS};
+
+lazy_static! {
+    static ref logger: Logger = Logger::default();
+}
+
+const FORMATS: [&str; 5] = ["TV", "TV_SHORT", "MOVIE", "ONA", "OVA"];
+const MAX_PAGES: i64 = 10;
+
+#[derive(Deserialize)]
+struct SeasonQuery {
+    sort:               Option<String>,
+    include_continuing: Option<bool>,
+}
+
+#[get("/season/current")]
+pub async fn current_season_chart(query: web::Query<SeasonQuery>) -> impl Responder {
+    let (season, year) = current_season();
+    season_chart(year, season.to_string(), query.into_inner()).await
+}
+
+#[get("/season/{year}/{season}")]
+pub async fn season_search(path: web::Path<(i32, String)>, query: web::Query<SeasonQuery>) -> impl Responder {
+    let (year, season) = path.into_inner();
+    season_chart(year, season.to_uppercase(), query.into_inner()).await
+}
+
+async fn season_chart(year: i32, season: String, query: SeasonQuery) -> HttpResponse {
+    let Some(started_before) = season_start(&season, year) else {
+        logger.error_single(&format!("Unknown season : {}", season), "Season");
+        return HttpResponse::BadRequest().json(json!({"error": "Unknown season", "seasons": SEASONS}));
+    };
+
+    let sort = query.sort.as_deref().unwrap_or("popularity").to_lowercase();
+    if !["popularity", "score", "start_date"].contains(&sort.as_str()) {
+        logger.error_single(&format!("Unknown season sort : {}", sort), "Season");
+        return HttpResponse::BadRequest().json(json!({"error": "sort must be popularity, score or start_date"}));
+    }
+
+    let include_continuing = query.include_continuing.unwrap_or(false);
+    let redis_key = format!("{}:{}:{}:{}", SEASON, year, season.to_lowercase(), include_continuing);
+
+    let mut chart = match cached_json(&redis_key) {
+        Some(chart) => {
+            logger.debug_single(&format!("Found {} {} chart in cache", season, year), "Season");
+            chart
+        },
+        None => {
+            let (current, current_year) = current_season();
+            let is_past = season_index(&season, year) < season_index(current, current_year);
+
+            let mut media = match fetch_season_media(json!({"season": season, "seasonYear": year})).await {
+                Ok(media) => media,
+                Err(response) => return response,
+            };
+
+            if include_continuing {
+                // Shows still airing now for the current season, or which ended during the season for past ones
+                let continuing = if is_past {
+                    json!({"startedBefore": started_before, "endedAfter": started_before})
+                } else {
+                    json!({"startedBefore": started_before, "status": "RELEASING"})
+                };
+
+                match fetch_season_media(continuing).await {
+                    Ok(continuing) => {
+                        for mut show in continuing {
+                            if media.iter().any(|existing| existing["id"] == show["id"]) {
+                                continue;
+                            }
+                            show["continuing"] = true.into();
+                            media.push(show);
+                        }
+                    },
+                    Err(response) => return response,
+                }
+            }
+
+            let mut groups = serde_json::Map::new();
+            for format in FORMATS {
+                let shows: Vec<&serde_json::Value> = media.iter().filter(|show| show["format"] == format).collect();
+                groups.insert(format.to_string(), json!(shows));
+            }
+
+            let chart = json!({
+                "season"    : season,
+                "year"      : year,
+                "total"     : media.len(),
+                "formats"   : groups,
+                "dataFrom"  : "API",
+            });
+
+            store_json(&redis_key, &chart, if is_past { 604800 } else { 21600 });
+            chart
+        }
+    };
+
+    for format in FORMATS {
+        if let Some(shows) = chart["formats"][format].as_array_mut() {
+            shows.sort_by(|a, b| compare_shows(a, b, &sort));
+        }
+    }
+
+    chart["sort"] = sort.into();
+    HttpResponse::Ok().json(chart)
+}
+
+fn compare_shows(a: &serde_json::Value, b: &serde_json::Value, sort: &str) -> Ordering {
+    let number = |show: &serde_json::Value, field: &str| show[field].as_i64().unwrap_or(i64::MIN);
+    match sort {
+        "score" => number(b, "score").cmp(&number(a, "score")),
+        "start_date" => {
+            let date = |show: &serde_json::Value| show["startDate"].as_i64().unwrap_or(i64::MAX);
+            date(a).cmp(&date(b))
+        },
+        _ => number(b, "popularity").cmp(&number(a, "popularity")),
+    }
+}
+
+async fn fetch_season_media(filters: serde_json::Value) -> Result<Vec<serde_json::Value>, HttpResponse> {
+    let mut media: Vec<serde_json::Value> = Vec::new();
+
+    for page in 1..=MAX_PAGES {
+        let mut variables = filters.clone();
+        variables["page"] = page.into();
+        variables["perPage"] = 50.into();
+
+        let chart = send_query("season_chart", variables, "Season").await?;
+        let data = &chart["data"]["Page"];
+
+        for show in data["media"].as_array().unwrap_or(&vec![]) {
+            media.push(wash_show(show));
+        }
+
+        if !data["pageInfo"]["hasNextPage"].as_bool().unwrap_or(false) {
+            break;
+        }
+    }
+
+    Ok(media)
+}
+
+fn wash_show(show: &serde_json::Value) -> serde_json::Value {
+    let studios: Vec<&serde_json::Value> = show["studios"]["nodes"]
+        .as_array()
+        .map(|studios| studios.iter().map(|studio| &studio["name"]).collect())
+        .unwrap_or_default();
+
+    json!({
+        "id"            : show["id"],
+        "romaji"        : show["title"]["romaji"],
+        "english"       : show["title"]["english"],
+        "native"        : show["title"]["native"],
+        "format"        : show["format"],
+        "status"        : show["status"],
+        "episodes"      : show["episodes"],
+        "genres"        : show["genres"],
+        "studios"       : studios,
+        "popularity"    : show["popularity"],
+        "score"         : if show["averageScore"].is_null() { &show["meanScore"] } else { &show["averageScore"] },
+        "isAdult"       : show["isAdult"],
+        "cover"         : show["coverImage"]["large"],
+        "url"           : show["siteUrl"],
+        "startDate"     : sortable_date(&show["startDate"]),
+        "nextEpisode"   : show["nextAiringEpisode"],
+        "continuing"    : false,
+    })
+}
@@ -0,0 +1,156 @@
+use serde::Deserialize;
+use serde_json::json;
+use actix_web::{web, get, post, HttpResponse, Responder};
+use colourful_logger::Logger;
+use lazy_static::lazy_static;
+use crate::anilist::client::send_query;
+use crate::cache::backend::{cached_json, store_json};
+use crate::cache::keys::STUDIO;
+use crate::global::compare_strings::compare_strings;
+
+lazy_static! {
+    static ref logger: Logger = Logger::default();
+}
+
+const STUDIO_SORTS: [&str; 7] = ["START_DATE_DESC", "START_DATE", "POPULARITY_DESC", "SCORE_DESC", "FAVOURITES_DESC", "TRENDING_DESC", "TITLE_ROMAJI"];
+
+#[derive(Deserialize)]
+struct StudioSearchRequest {
+    studio_name: String,
+}
+
+#[derive(Deserialize)]
+struct StudioQuery {
+    page:       Option<i64>,
+    per_page:   Option<i64>,
+    sort:       Option<String>,
+    main_only:  Option<bool>,
+}
+
+#[post("/studio/search")]
+pub async fn studio_search(req: web::Json<StudioSearchRequest>) -> impl Responder {
+    let studio_name = req.studio_name.trim().to_lowercase();
+    if studio_name.is_empty() {
+        logger.error_single("No studio name was included", "Studio");
+        return HttpResponse::BadRequest().json(json!({"error": "No studio name was included"}));
+    }
+
+    let redis_key = format!("{}:search:{}", STUDIO, studio_name);
+    if let Some(studios) = cached_json(&redis_key) {
+        logger.debug_single(&format!("Found studio search for {} in cache", studio_name), "Studio");
+        return HttpResponse::Ok().json(studios);
+    }
+
+    let variables = json!({"search": studio_name, "page": 1, "perPage": 25});
+    let studios = match send_query("studio_search", variables, "Studio").await {
+        Ok(studios) => wash_studio_search(&studio_name, studios),
+        Err(response) => return response,
+    };
+
+    store_json(&redis_key, &studios, 86400);
+    HttpResponse::Ok().json(studios)
+}
+
+#[get("/studio/{id}")]
+pub async fn studio_details(path: web::Path<i64>, query: web::Query<StudioQuery>) -> impl Responder {
+    let studio_id = path.into_inner();
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(25).clamp(1, 50);
+    let sort = query.sort.as_deref().unwrap_or("START_DATE_DESC").to_uppercase();
+    let main_only = query.main_only.unwrap_or(false);
+
+    if !STUDIO_SORTS.contains(&sort.as_str()) {
+        logger.error_single(&format!("Unknown studio sort : {}", sort), "Studio");
+        return HttpResponse::BadRequest().json(json!({"error": "Unknown sort", "sorts": STUDIO_SORTS}));
+    }
+
+    let redis_key = format!("{}:{}:{}:{}:{}:{}", STUDIO, studio_id, page, per_page, sort.to_lowercase(), main_only);
+    if let Some(studio) = cached_json(&redis_key) {
+        logger.debug_single(&format!("Found studio {} in cache", studio_id), "Studio");
+        return HttpResponse::Ok().json(studio);
+    }
+
+    let variables = json!({
+        "id"        : studio_id,
+        "page"      : page,
+        "perPage"   : per_page,
+        "sort"      : [sort],
+        "isMain"    : if main_only { Some(true) } else { None },
+    });
+
+    let studio = match send_query("studio", variables, "Studio").await {
+        Ok(studio) => wash_studio(studio),
+        Err(response) => return response,
+    };
+
+    store_json(&redis_key, &studio, 86400);
+    HttpResponse::Ok().json(studio)
+}
+
+fn wash_studio_search(studio_name: &String, studios: serde_json::Value) -> serde_json::Value {
+    logger.debug_single("Washing up studio search data", "Studio");
+    let mut washed_studios: Vec<serde_json::Value> = Vec::new();
+
+    for studio in studios["data"]["Page"]["studios"].as_array().unwrap_or(&vec![]) {
+        let name = studio["name"].as_str().unwrap_or("").to_lowercase();
+        let similarity = compare_strings(studio_name, vec![&name])
+            .first()
+            .map_or(0.0, |(_, similarity)| *similarity);
+
+        washed_studios.push(json!({
+            "id"                : studio["id"],
+            "name"              : studio["name"],
+            "isAnimationStudio" : studio["isAnimationStudio"],
+            "favourites"        : studio["favourites"],
+            "url"               : studio["siteUrl"],
+            "similarity"        : similarity,
+        }));
+    }
+
+    washed_studios.sort_by(|a, b| b["similarity"].as_f64().unwrap().partial_cmp(&a["similarity"].as_f64().unwrap()).unwrap());
+    json!({
+        "studios"   : washed_studios,
+        "dataFrom"  : "API",
+    })
+}
+
+fn wash_studio(studio: serde_json::Value) -> serde_json::Value {
+    logger.debug_single("Washing up studio data", "Studio");
+    let data = &studio["data"]["Studio"];
+
+    let productions: Vec<serde_json::Value> = data["media"]["edges"]
+        .as_array()
+        .unwrap_or(&vec![])
+        .iter()
+        .map(|edge| {
+            let media = &edge["node"];
+            let year = if media["seasonYear"].is_null() { &media["startDate"]["year"] } else { &media["seasonYear"] };
+            let score = if media["averageScore"].is_null() { &media["meanScore"] } else { &media["averageScore"] };
+            json!({
+                "id"            : media["id"],
+                "romaji"        : media["title"]["romaji"],
+                "english"       : media["title"]["english"],
+                "type"          : media["type"],
+                "format"        : media["format"],
+                "status"        : media["status"],
+                "year"          : year,
+                "score"         : score,
+                "popularity"    : media["popularity"],
+                "cover"         : media["coverImage"]["large"],
+                "url"           : media["siteUrl"],
+                "isMainStudio"  : edge["isMainStudio"],
+            })
+        })
+        .collect();
+
+    json!({
+        "id"                : data["id"],
+        "name"              : data["name"],
+        "isAnimationStudio" : data["isAnimationStudio"],
+        "favourites"        : data["favourites"],
+        "url"               : data["siteUrl"],
+        "pageInfo"          : data["media"]["pageInfo"],
+        "productions"       : productions,
+        "dataFrom"          : "API",
+    })
+}
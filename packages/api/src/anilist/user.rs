@@ -5,12 +5,13 @@ use actix_web::{web, post, HttpResponse, Responder};
 use colourful_logger::Logger;
 use crate::anilist::queries::{get_query, QUERY_URL};
 use lazy_static::lazy_static;
-use crate::cache::redis::Redis;
+use crate::cache::backend::cache;
+use crate::cache::keys::{score_key, user_key};
+use crate::cache::negative::{cache_not_found, cached_not_found, clear_cached};
 use crate::cache::proxy::{get_random_proxy, remove_proxy};
 
 lazy_static! {
     static ref logger: Logger = Logger::default();
-    static ref redis:  Redis  = Redis::new();
 }
 
 #[derive(Deserialize, Debug)]
@@ -19,6 +20,11 @@ struct ScoreRequest {
     media_id: i64,
 }
 
+#[derive(Deserialize)]
+struct RefreshQuery {
+    refresh: Option<bool>,
+}
+
 #[derive(Deserialize)]
 struct UserRequest {
     user_id: String,
@@ -26,14 +32,14 @@ struct UserRequest {
 
 #[post("/user/score")]
 pub async fn user_score(req: web::Json<ScoreRequest>) -> impl Responder {
-    let redis_key = req.media_id.to_string() + ":" + req.user_id.to_string().as_str();
+    let redis_key = score_key(req.media_id, req.user_id);
 
-    match redis.get(redis_key.clone()) {
+    match cache.get(&redis_key) {
         Ok(data) => {
             logger.debug_single(&format!("Found data for {}, returning data for ID : {}", req.user_id, req.media_id), "User Score");
             let mut user_data: serde_json::Value = serde_json::from_str(data.as_str()).unwrap();
             user_data["dataFrom"] = "Cache".into();
-            user_data["leftUntilExpire"] = redis.ttl(redis_key.to_string()).unwrap().into();
+            user_data["leftUntilExpire"] = cache.ttl(&redis_key).unwrap().into();
             return HttpResponse::Ok().json(user_data);
         },
         Err(_) => {
@@ -41,7 +47,7 @@ pub async fn user_score(req: web::Json<ScoreRequest>) -> impl Responder {
         }
     }
 
-    let get_proxy = get_random_proxy(&redis.get_client()).await.unwrap();
+    let get_proxy = get_random_proxy().await.unwrap();
     let proxy = reqwest::Proxy::http(get_proxy.clone()).unwrap();
     let client = Client::builder().proxy(proxy).build().unwrap();
     let user_query = get_query("user_stats");
@@ -57,7 +63,7 @@ pub async fn user_score(req: web::Json<ScoreRequest>) -> impl Responder {
     
     if response.status().as_u16() != 200 {
         if response.status().as_u16() == 403 {
-            let _ = remove_proxy(&redis.get_client(), get_proxy.as_str()).await;
+            let _ = remove_proxy(get_proxy.as_str()).await;
         }
 
         logger.error_single(format!("Request returned {} when trying to fetch {}", response.status().as_str(), req.user_id).as_str(), "User Score");
@@ -70,15 +76,15 @@ pub async fn user_score(req: web::Json<ScoreRequest>) -> impl Responder {
 
     let user = wash_user_score(user).await;
 
-    redis.set(redis_key.clone(), user.to_string()).unwrap();
-    redis.expire(redis_key, 86400).unwrap();
+    cache.set(&redis_key, &user.to_string()).unwrap();
+    cache.expire(&redis_key, 86400).unwrap();
 
     logger.debug_single(format!("Returning JSON data for user ID: {}", req.user_id).as_str(), "User Score");
     HttpResponse::Ok().json(user)
 }
 
 #[post("/user")]
-pub async fn user_search(username: String) -> impl Responder {
+pub async fn user_search(username: String, query: web::Query<RefreshQuery>) -> impl Responder {
 
     if username.len() == 0 {
         logger.error_single("No username was included", "User");
@@ -86,12 +92,24 @@ pub async fn user_search(username: String) -> impl Responder {
         return HttpResponse::BadRequest().json(bad_json);
     }
 
-    match redis.get(username.clone()) {
+    let redis_key = user_key(&username);
+    if query.refresh.unwrap_or(false) {
+        logger.debug_single(&format!("Refreshing cached data for {}", username), "User");
+        clear_cached(&redis_key);
+    }
+
+    if let Some(ttl) = cached_not_found(&redis_key) {
+        logger.debug_single(&format!("{} is cached as not found", username), "User");
+        let not_found = json!({"error": "User not found", "errorCode": 404, "dataFrom": "Cache", "leftUntilExpire": ttl});
+        return HttpResponse::NotFound().json(not_found);
+    }
+
+    match cache.get(&redis_key) {
         Ok(data) => {
             logger.debug_single(&format!("Found {} data in cache. Returning cached data", username), "User");
             let mut user_data: serde_json::Value = serde_json::from_str(data.as_str()).unwrap();
             user_data["dataFrom"] = "Cache".into();
-            user_data["leftUntilExpire"] = redis.ttl(username.to_string()).unwrap().into();
+            user_data["leftUntilExpire"] = cache.ttl(&redis_key).unwrap().into();
             return HttpResponse::Ok().json(user_data);
         },
         Err(_) => {
@@ -99,7 +117,7 @@ pub async fn user_search(username: String) -> impl Responder {
         }
     }
 
-    let get_proxy = get_random_proxy(&redis.get_client()).await.unwrap();
+    let get_proxy = get_random_proxy().await.unwrap();
     let proxy = reqwest::Proxy::http(get_proxy.clone()).unwrap();
     let client = Client::builder().proxy(proxy).build().unwrap();
     let user_query = get_query("user");
@@ -115,7 +133,14 @@ pub async fn user_search(username: String) -> impl Responder {
     
     if response.status().as_u16() != 200 {
         if response.status().as_u16() == 403 {
-            let _ = remove_proxy(&redis.get_client(), get_proxy.as_str()).await;
+            let _ = remove_proxy(get_proxy.as_str()).await;
+        }
+
+        if response.status().as_u16() == 404 {
+            logger.warn_single(&format!("{} was not found on Anilist", username), "User");
+            cache_not_found(&redis_key);
+            let not_found = json!({"error": "User not found", "errorCode": 404, "dataFrom": "API"});
+            return HttpResponse::NotFound().json(not_found);
         }
         
         logger.error_single(format!("Request returned {} when trying to fetch {}", response.status().as_str(), username).as_str(), "User");
@@ -127,8 +152,8 @@ pub async fn user_search(username: String) -> impl Responder {
     logger.debug("Washing up user score data", "User Score", false, user.clone());
     let user = wash_user_data(user).await;
 
-    redis.set(username.clone(), user.to_string()).unwrap();
-    redis.expire(username.clone(), 86400).unwrap();
+    cache.set(&redis_key, &user.to_string()).unwrap();
+    cache.expire(&redis_key, 86400).unwrap();
 
     logger.debug_single(format!("Returning JSON data for user: {}", username).as_str(), "User");
     HttpResponse::Ok().json(user)
@@ -137,7 +162,7 @@ pub async fn user_search(username: String) -> impl Responder {
 #[post("/expire-user")]
 async fn expire(req: web::Json<UserRequest>) -> impl Responder {
     
-    match redis.expire_user(&req.user_id).await {
+    match cache.expire_user(&req.user_id) {
         Ok(_) => {
             HttpResponse::Ok().json(json!({
                 "status": "success",
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::env;
+use serde::Deserialize;
+use serde_json::json;
+use actix_web::{web, get, post, HttpRequest, HttpResponse, Responder};
+use colourful_logger::Logger;
+use lazy_static::lazy_static;
+use crate::cache::backend::cache;
+use crate::cache::keys::{entity_key, escape_glob, namespace_pattern, not_found_key, subkey_kind, NAMESPACES, NESTED_NAMESPACES, NOT_FOUND};
+
+lazy_static! {
+    static ref logger: Logger = Logger::default();
+}
+
+#[derive(Deserialize)]
+struct CacheEntryRequest {
+    entity: String,
+    id:     String,
+}
+
+#[derive(Deserialize)]
+struct CacheTypeRequest {
+    entity: String,
+}
+
+fn authorised(req: &HttpRequest) -> Option<HttpResponse> {
+    let token = match env::var("ADMIN_TOKEN") {
+        Ok(token) if !token.is_empty() => token,
+        _ => {
+            logger.warn_single("Admin endpoint called but ADMIN_TOKEN is not set", "Admin");
+            return Some(HttpResponse::Forbidden().json(json!({"error": "Admin endpoints are disabled"})));
+        }
+    };
+
+    let header = req.headers().get("Authorization").and_then(|value| value.to_str().ok()).unwrap_or("");
+    let given = header.strip_prefix("Bearer ").unwrap_or("");
+    if !constant_time_eq(given.as_bytes(), token.as_bytes()) {
+        logger.warn_single("Admin endpoint called with an invalid token", "Admin");
+        return Some(HttpResponse::Unauthorized().json(json!({"error": "Invalid admin token"})));
+    }
+
+    None
+}
+
+/// Compares every byte regardless of where the first difference is, so response times do not leak the token.
+fn constant_time_eq(given: &[u8], expected: &[u8]) -> bool {
+    if given.len() != expected.len() {
+        return false;
+    }
+    given.iter().zip(expected).fold(0u8, |difference, (a, b)| difference | (a ^ b)) == 0
+}
+
+fn unknown_entity(entity: &str) -> HttpResponse {
+    logger.error_single(&format!("Unknown cache entity type : {}", entity), "Admin");
+    HttpResponse::BadRequest().json(json!({"error": "Unknown entity type", "entities": NAMESPACES}))
+}
+
+#[post("/admin/cache/lookup")]
+pub async fn cache_lookup(http: HttpRequest, req: web::Json<CacheEntryRequest>) -> impl Responder {
+    if let Some(denied) = authorised(&http) {
+        return denied;
+    }
+
+    let Some(redis_key) = entity_key(&req.entity, &req.id) else {
+        return unknown_entity(&req.entity);
+    };
+
+    match cache.get(&redis_key) {
+        Ok(data) => {
+            let data: serde_json::Value = serde_json::from_str(data.as_str()).unwrap();
+            HttpResponse::Ok().json(json!({
+                "key"   : redis_key,
+                "entity": req.entity.to_lowercase(),
+                "ttl"   : cache.ttl(&redis_key).unwrap_or(-2),
+                "source": data["dataFrom"],
+                "data"  : data,
+            }))
+        },
+        Err(_) => HttpResponse::NotFound().json(json!({"error": "No cached entry found", "key": redis_key})),
+    }
+}
+
+#[post("/admin/cache/purge")]
+pub async fn cache_purge(http: HttpRequest, req: web::Json<CacheEntryRequest>) -> impl Responder {
+    if let Some(denied) = authorised(&http) {
+        return denied;
+    }
+
+    let Some(redis_key) = entity_key(&req.entity, &req.id) else {
+        return unknown_entity(&req.entity);
+    };
+
+    // Characters, staff and other data cached alongside the entry live under its key, as do tombstones kept per media type
+    let mut related = cache.delete(&not_found_key(&redis_key)).unwrap_or(0);
+    let pattern = escape_glob(&redis_key);
+    for pattern in [namespace_pattern(&pattern), namespace_pattern(&not_found_key(&pattern))] {
+        for key in cache.scan_keys(&pattern).unwrap_or_default() {
+            related += cache.delete(&key).unwrap_or(0);
+        }
+    }
+
+    match cache.delete(&redis_key) {
+        Ok(count) if count + related == 0 => HttpResponse::NotFound().json(json!({"error": "No cached entry found", "key": redis_key})),
+        Ok(_) => {
+            logger.info_single(&format!("Purged cached entry {}", redis_key), "Admin");
+            HttpResponse::Ok().json(json!({"status": "success", "key": redis_key}))
+        },
+        Err(e) => HttpResponse::InternalServerError().json(json!({"status": "error", "message": e.to_string()})),
+    }
+}
+
+#[post("/admin/cache/purge-type")]
+pub async fn cache_purge_type(http: HttpRequest, req: web::Json<CacheTypeRequest>) -> impl Responder {
+    if let Some(denied) = authorised(&http) {
+        return denied;
+    }
+
+    let entity = req.entity.to_lowercase();
+    if !NAMESPACES.contains(&entity.as_str()) {
+        return unknown_entity(&req.entity);
+    }
+
+    let mut keys = vec![];
+    for pattern in [namespace_pattern(&entity), namespace_pattern(&not_found_key(&entity))] {
+        match cache.scan_keys(&pattern) {
+            Ok(found) => keys.extend(found),
+            Err(e) => return HttpResponse::InternalServerError().json(json!({"status": "error", "message": e.to_string()})),
+        }
+    }
+
+    let mut purged = 0;
+    for key in keys {
+        purged += cache.delete(&key).unwrap_or(0);
+    }
+
+    logger.info_single(&format!("Purged {} cached {} entries", purged, entity), "Admin");
+    HttpResponse::Ok().json(json!({"status": "success", "entity": entity, "purged": purged}))
+}
+
+#[get("/admin/cache/stats")]
+pub async fn cache_stats(http: HttpRequest) -> impl Responder {
+    if let Some(denied) = authorised(&http) {
+        return denied;
+    }
+
+    let mut stats = serde_json::Map::new();
+    for namespace in NAMESPACES.iter().chain([&NOT_FOUND]) {
+        let keys = match cache.scan_keys(&namespace_pattern(namespace)) {
+            Ok(keys) => keys,
+            Err(e) => return HttpResponse::InternalServerError().json(json!({"status": "error", "message": e.to_string()})),
+        };
+
+        let memory: Vec<i64> = cache.memory_usage(&keys)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|usage| usage.unwrap_or(0))
+            .collect();
+
+        // Data cached under an entry (characters, lists...) is counted on its own, so `count` is the number of entries
+        let mut totals: HashMap<&str, (usize, i64)> = HashMap::new();
+        for (index, key) in keys.iter().enumerate() {
+            let kind = match NESTED_NAMESPACES.contains(namespace) {
+                true => subkey_kind(key).unwrap_or(""),
+                false => "",
+            };
+            let total = totals.entry(kind).or_default();
+            total.0 += 1;
+            total.1 += memory.get(index).copied().unwrap_or(0);
+        }
+
+        let (count, memory_bytes) = totals.remove("").unwrap_or_default();
+        let mut namespace_stats = json!({
+            "count"      : count,
+            "memoryBytes": memory_bytes,
+        });
+
+        if NESTED_NAMESPACES.contains(namespace) {
+            let subkeys: serde_json::Map<String, serde_json::Value> = totals
+                .into_iter()
+                .map(|(kind, (count, memory_bytes))| (kind.to_string(), json!({"count": count, "memoryBytes": memory_bytes})))
+                .collect();
+            namespace_stats["subkeys"] = subkeys.into();
+        }
+
+        stats.insert(namespace.to_string(), namespace_stats);
+    }
+
+    HttpResponse::Ok().json(stats)
+}
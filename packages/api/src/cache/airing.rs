@@ -0,0 +1,55 @@
+use std::env;
+use std::time::{SystemTime, UNIX_EPOCH};
+use colourful_logger::Logger;
+use lazy_static::lazy_static;
+use crate::cache::backend::cache;
+use crate::cache::keys::{AIRING_EPISODES, AIRING_PENDING, AIRING_STREAM};
+
+lazy_static! {
+    static ref logger: Logger = Logger::default();
+}
+
+/// Publishes an "episode aired" event to the airing stream for every scheduled episode whose airing time has passed.
+pub async fn publish_aired_episodes() {
+    let interval = env::var("AIRING_POLL_INTERVAL").ok().and_then(|value| value.parse::<u64>().ok()).unwrap_or(60).max(1);
+
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+
+        let due = match cache.due(AIRING_PENDING, now) {
+            Ok(due) => due,
+            Err(e) => {
+                logger.error_single(&format!("Failed to read airing schedule: {:?}", e), "Airing");
+                continue;
+            }
+        };
+
+        for member in due {
+            // Only the instance that removes the entry publishes it, so events are not duplicated
+            if cache.unschedule(AIRING_PENDING, &member).unwrap_or(0) == 0 {
+                continue;
+            }
+
+            let event = match cache.hash_take(AIRING_EPISODES, &member) {
+                Ok(Some(event)) => serde_json::from_str::<serde_json::Value>(&event).unwrap_or_default(),
+                _ => {
+                    logger.warn_single(&format!("No airing details stored for {}", member), "Airing");
+                    continue;
+                }
+            };
+
+            let fields = [
+                ("mediaId", event["mediaId"].to_string()),
+                ("title",   event["title"].as_str().unwrap_or("").to_string()),
+                ("episode", event["episode"].to_string()),
+                ("airedAt", event["airingAt"].to_string()),
+            ];
+
+            match cache.publish(AIRING_STREAM, &fields) {
+                Ok(id) => logger.info_single(&format!("Published episode {} of {} ({})", event["episode"], event["title"], id), "Airing"),
+                Err(e) => logger.error_single(&format!("Failed to publish airing event for {}: {:?}", member, e), "Airing"),
+            }
+        }
+    }
+}
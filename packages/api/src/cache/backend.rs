@@ -0,0 +1,79 @@
+use std::env;
+use redis::RedisResult;
+use colourful_logger::Logger;
+use lazy_static::lazy_static;
+use crate::cache::keys::{escape_glob, SCORE};
+use crate::cache::memory::Memory;
+use crate::cache::redis::Redis;
+
+lazy_static! {
+    static ref logger: Logger = Logger::default();
+    pub static ref cache: Box<dyn CacheBackend> = from_env();
+}
+
+/// Storage used for every cached entry, proxy list and airing schedule.
+pub trait CacheBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn get(&self, key: &str) -> RedisResult<String>;
+    fn set(&self, key: &str, value: &str) -> RedisResult<()>;
+    fn expire(&self, key: &str, seconds: i64) -> RedisResult<()>;
+    fn ttl(&self, key: &str) -> RedisResult<i64>;
+    fn delete(&self, key: &str) -> RedisResult<i64>;
+
+    fn scan_keys(&self, pattern: &str) -> RedisResult<Vec<String>>;
+    fn memory_usage(&self, keys: &[String]) -> RedisResult<Vec<Option<i64>>>;
+
+    fn hash_set(&self, key: &str, field: &str, value: &str) -> RedisResult<()>;
+    fn hash_get_all(&self, key: &str) -> RedisResult<Vec<(String, String)>>;
+    fn hash_take(&self, key: &str, field: &str) -> RedisResult<Option<String>>;
+    fn hash_delete(&self, key: &str, field: &str) -> RedisResult<()>;
+
+    fn schedule(&self, key: &str, member: &str, score: i64) -> RedisResult<()>;
+    fn due(&self, key: &str, until: i64) -> RedisResult<Vec<String>>;
+    fn unschedule(&self, key: &str, member: &str) -> RedisResult<i64>;
+
+    fn publish(&self, stream: &str, fields: &[(&str, String)]) -> RedisResult<String>;
+
+    fn expire_user(&self, user_id: &str) -> RedisResult<()> {
+        logger.debug_single(&format!("Deleting all cached related for user ID {:?}", user_id), "Cache");
+        // The id is escaped so it only matches itself, otherwise an id of `*` would remove every user's scores
+        let keys = self.scan_keys(&format!("{}:*:{}", SCORE, escape_glob(user_id)))?;
+
+        if keys.is_empty() {
+            logger.warn_single("No keys found for user ID", "Cache");
+            return Err(redis::RedisError::from((redis::ErrorKind::ResponseError, "No keys found for user ID")));
+        }
+
+        for key in keys {
+            logger.debug_single(&format!("Found Key: {:?}", key), "Cache");
+            self.delete(&key)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the cached JSON for `key` marked as coming from the cache, along with how long it has left.
+pub fn cached_json(key: &str) -> Option<serde_json::Value> {
+    let data = cache.get(key).ok()?;
+    let mut data: serde_json::Value = serde_json::from_str(data.as_str()).ok()?;
+    data["dataFrom"] = "Cache".into();
+    data["leftUntilExpire"] = cache.ttl(key).unwrap_or(-1).into();
+    Some(data)
+}
+
+pub fn store_json(key: &str, data: &serde_json::Value, seconds: i64) {
+    let _ = cache.set(key, &data.to_string());
+    let _ = cache.expire(key, seconds);
+}
+
+fn from_env() -> Box<dyn CacheBackend> {
+    let backend: Box<dyn CacheBackend> = match env::var("CACHE_BACKEND").unwrap_or_default().to_lowercase().as_str() {
+        "memory" => Box::new(Memory::new()),
+        _ => Box::new(Redis::new()),
+    };
+
+    logger.info_single(&format!("Using {} cache backend", backend.name()), "Cache");
+    backend
+}
@@ -0,0 +1,75 @@
+use std::fmt::Display;
+
+pub const MEDIA: &str = "media";
+pub const USER: &str = "user";
+pub const SCORE: &str = "score";
+pub const TITLE: &str = "title";
+pub const STUDIO: &str = "studio";
+pub const SCHEDULE: &str = "schedule";
+pub const SEASON: &str = "season";
+pub const RANKING: &str = "ranking";
+pub const SEARCH: &str = "search";
+pub const NOT_FOUND: &str = "notfound";
+
+pub const AIRING_PENDING: &str = "airing:pending";
+pub const AIRING_EPISODES: &str = "airing:episodes";
+pub const AIRING_STREAM: &str = "airing:events";
+
+pub const NAMESPACES: [&str; 9] = [MEDIA, USER, SCORE, TITLE, STUDIO, SCHEDULE, SEASON, RANKING, SEARCH];
+
+/// Namespaces whose entries have data cached under their own key, e.g. `media:<id>:characters:...` or `user:<name>:list:...`.
+pub const NESTED_NAMESPACES: [&str; 2] = [MEDIA, USER];
+
+pub fn media_key<T: Display>(media_id: T) -> String {
+    format!("{}:{}", MEDIA, media_id)
+}
+
+pub fn user_key(username: &str) -> String {
+    format!("{}:{}", USER, username.to_lowercase())
+}
+
+pub fn title_key(media_type: &str, title: &str) -> String {
+    format!("{}:{}:{}", TITLE, media_type.to_lowercase(), title.trim().to_lowercase())
+}
+
+/// Tombstone marking that Anilist returned 404 for the entry stored under `redis_key`.
+pub fn not_found_key(redis_key: &str) -> String {
+    format!("{}:{}", NOT_FOUND, redis_key)
+}
+
+pub fn score_key<T: Display, U: Display>(media_id: T, user_id: U) -> String {
+    format!("{}:{}:{}", SCORE, media_id, user_id)
+}
+
+/// Builds the key for an entity type and identifier, scores use `<media_id>:<user_id>` and titles `<media_type>:<title>` as their identifier.
+pub fn entity_key(entity: &str, id: &str) -> Option<String> {
+    match entity.to_lowercase().as_str() {
+        MEDIA => Some(media_key(id)),
+        USER => Some(user_key(id)),
+        SCORE => Some(format!("{}:{}", SCORE, id)),
+        TITLE => Some(format!("{}:{}", TITLE, id.trim().to_lowercase())),
+        namespace if NAMESPACES.contains(&namespace) => Some(format!("{}:{}", namespace, id)),
+        _ => None,
+    }
+}
+
+pub fn namespace_pattern(namespace: &str) -> String {
+    format!("{}:*", namespace)
+}
+
+/// Escapes glob characters, so user supplied identifiers only ever match themselves in a key pattern.
+pub fn escape_glob(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// The kind of data cached under an entry of a nested namespace (e.g. `characters`), `None` for the entry itself.
+pub fn subkey_kind(key: &str) -> Option<&str> {
+    key.split(':').nth(2)
+}
@@ -0,0 +1,366 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use redis::RedisResult;
+use colourful_logger::Logger as Logger;
+use lazy_static::lazy_static;
+use crate::cache::backend::CacheBackend;
+
+lazy_static! {
+    static ref logger: Logger = Logger::default();
+}
+
+const STREAM_LENGTH: usize = 1000;
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+enum Value {
+    String(String),
+    Hash(HashMap<String, String>),
+    SortedSet(HashMap<String, i64>),
+    Stream(VecDeque<(String, Vec<(String, String)>)>),
+}
+
+struct Entry {
+    value:      Value,
+    expires_at: Option<Instant>,
+}
+
+impl Entry {
+    fn new(value: Value) -> Self {
+        Entry { value, expires_at: None }
+    }
+
+    fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= Instant::now())
+    }
+
+    fn size(&self) -> usize {
+        match &self.value {
+            Value::String(value) => value.len(),
+            Value::Hash(hash) => hash.iter().map(|(field, value)| field.len() + value.len()).sum(),
+            Value::SortedSet(set) => set.keys().map(|member| member.len() + 8).sum(),
+            Value::Stream(stream) => stream.iter()
+                .map(|(id, fields)| id.len() + fields.iter().map(|(field, value)| field.len() + value.len()).sum::<usize>())
+                .sum(),
+        }
+    }
+}
+
+/// In-process cache backend, used when no Redis/KeyDB instance is available.
+pub struct Memory {
+    entries:  Mutex<HashMap<String, Entry>>,
+    sequence: AtomicU64,
+    swept_at: Mutex<Instant>,
+}
+
+fn wrong_type() -> redis::RedisError {
+    redis::RedisError::from((redis::ErrorKind::TypeError, "Operation against a key holding the wrong kind of value"))
+}
+
+/// Matches keys against Redis style glob patterns, only `*`, `?` and `\` escapes are supported.
+fn glob_match(pattern: &str, key: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let key: Vec<char> = key.chars().collect();
+    let (mut p, mut k) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while k < key.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, k));
+            p += 1;
+            continue;
+        }
+
+        let width = match pattern.get(p) {
+            Some('\\') if p + 1 < pattern.len() => (pattern[p + 1] == key[k]).then_some(2),
+            Some('?') => Some(1),
+            Some(c) => (*c == key[k]).then_some(1),
+            None => None,
+        };
+
+        if let Some(width) = width {
+            p += width;
+            k += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            k = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+impl Memory {
+    pub fn new() -> Self {
+        logger.debug_single("Created in-memory cache", "Memory");
+        Memory {
+            entries:  Mutex::new(HashMap::new()),
+            sequence: AtomicU64::new(0),
+            swept_at: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Expired entries are otherwise only dropped when read, so keys that are never read again are swept here.
+    fn sweep(&self, entries: &mut HashMap<String, Entry>) {
+        let mut swept_at = self.swept_at.lock().unwrap();
+        if swept_at.elapsed() < SWEEP_INTERVAL {
+            return;
+        }
+
+        let before = entries.len();
+        entries.retain(|_, entry| !entry.is_expired());
+        *swept_at = Instant::now();
+        logger.debug_single(&format!("Swept {} expired keys", before - entries.len()), "Memory");
+    }
+
+    /// Runs `action` against the live entry for `key`, dropping it first if it has expired.
+    fn with_entry<R>(&self, key: &str, action: impl FnOnce(Option<&mut Entry>) -> R) -> R {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.get(key).is_some_and(|entry| entry.is_expired()) {
+            entries.remove(key);
+        }
+        action(entries.get_mut(key))
+    }
+
+    fn with_entry_or<R>(&self, key: &str, default: Value, action: impl FnOnce(&mut Entry) -> R) -> R {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.get(key).is_some_and(|entry| entry.is_expired()) {
+            entries.remove(key);
+        }
+        action(entries.entry(key.to_string()).or_insert_with(|| Entry::new(default)))
+    }
+}
+
+impl CacheBackend for Memory {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn get(&self, key: &str) -> RedisResult<String> {
+        logger.debug_single(&format!("Trying to grab key : {:?}", key), "Memory");
+        self.with_entry(key, |entry| match entry.map(|entry| &entry.value) {
+            Some(Value::String(data)) => Ok(data.clone()),
+            Some(_) => Err(wrong_type()),
+            None => {
+                logger.warn_single("No value found for key", "Memory");
+                Err(redis::RedisError::from((redis::ErrorKind::ResponseError, "No value found for key")))
+            }
+        })
+    }
+
+    fn set(&self, key: &str, value: &str) -> RedisResult<()> {
+        logger.debug_single(&format!("Setting Key with data {:?}", key), "Memory");
+        let mut entries = self.entries.lock().unwrap();
+        self.sweep(&mut entries);
+        entries.insert(key.to_string(), Entry::new(Value::String(value.to_string())));
+        Ok(())
+    }
+
+    fn expire(&self, key: &str, seconds: i64) -> RedisResult<()> {
+        logger.debug_single(&format!("Setting Key to expire in {} seconds : {:?}", seconds, key), "Memory");
+        let mut entries = self.entries.lock().unwrap();
+        if seconds <= 0 {
+            entries.remove(key);
+        } else if let Some(entry) = entries.get_mut(key) {
+            entry.expires_at = Some(Instant::now() + Duration::from_secs(seconds as u64));
+        }
+        Ok(())
+    }
+
+    fn ttl(&self, key: &str) -> RedisResult<i64> {
+        self.with_entry(key, |entry| match entry {
+            Some(Entry { expires_at: Some(expires_at), .. }) => {
+                Ok(expires_at.saturating_duration_since(Instant::now()).as_secs() as i64)
+            },
+            Some(_) => Ok(-1),
+            None => Ok(-2),
+        })
+    }
+
+    fn delete(&self, key: &str) -> RedisResult<i64> {
+        logger.debug_single(&format!("Deleting key : {:?}", key), "Memory");
+        let removed = self.entries.lock().unwrap().remove(key);
+        Ok(removed.is_some_and(|entry| !entry.is_expired()) as i64)
+    }
+
+    fn scan_keys(&self, pattern: &str) -> RedisResult<Vec<String>> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, entry| !entry.is_expired());
+        Ok(entries.keys().filter(|key| glob_match(pattern, key)).cloned().collect())
+    }
+
+    fn memory_usage(&self, keys: &[String]) -> RedisResult<Vec<Option<i64>>> {
+        let entries = self.entries.lock().unwrap();
+        Ok(keys.iter()
+            .map(|key| entries.get(key).filter(|entry| !entry.is_expired()).map(|entry| (key.len() + entry.size()) as i64))
+            .collect())
+    }
+
+    fn hash_set(&self, key: &str, field: &str, value: &str) -> RedisResult<()> {
+        self.with_entry_or(key, Value::Hash(HashMap::new()), |entry| match &mut entry.value {
+            Value::Hash(hash) => {
+                hash.insert(field.to_string(), value.to_string());
+                Ok(())
+            },
+            _ => Err(wrong_type()),
+        })
+    }
+
+    fn hash_get_all(&self, key: &str) -> RedisResult<Vec<(String, String)>> {
+        self.with_entry(key, |entry| match entry.map(|entry| &entry.value) {
+            Some(Value::Hash(hash)) => Ok(hash.iter().map(|(field, value)| (field.clone(), value.clone())).collect()),
+            Some(_) => Err(wrong_type()),
+            None => Ok(vec![]),
+        })
+    }
+
+    fn hash_take(&self, key: &str, field: &str) -> RedisResult<Option<String>> {
+        self.with_entry(key, |entry| match entry.map(|entry| &mut entry.value) {
+            Some(Value::Hash(hash)) => Ok(hash.remove(field)),
+            Some(_) => Err(wrong_type()),
+            None => Ok(None),
+        })
+    }
+
+    fn hash_delete(&self, key: &str, field: &str) -> RedisResult<()> {
+        self.hash_take(key, field).map(|_| ())
+    }
+
+    fn schedule(&self, key: &str, member: &str, score: i64) -> RedisResult<()> {
+        self.with_entry_or(key, Value::SortedSet(HashMap::new()), |entry| match &mut entry.value {
+            Value::SortedSet(set) => {
+                set.insert(member.to_string(), score);
+                Ok(())
+            },
+            _ => Err(wrong_type()),
+        })
+    }
+
+    fn due(&self, key: &str, until: i64) -> RedisResult<Vec<String>> {
+        self.with_entry(key, |entry| match entry.map(|entry| &entry.value) {
+            Some(Value::SortedSet(set)) => {
+                let mut due: Vec<(&String, &i64)> = set.iter().filter(|(_, score)| **score <= until).collect();
+                due.sort_by_key(|(_, score)| **score);
+                Ok(due.into_iter().map(|(member, _)| member.clone()).collect())
+            },
+            Some(_) => Err(wrong_type()),
+            None => Ok(vec![]),
+        })
+    }
+
+    fn unschedule(&self, key: &str, member: &str) -> RedisResult<i64> {
+        self.with_entry(key, |entry| match entry.map(|entry| &mut entry.value) {
+            Some(Value::SortedSet(set)) => Ok(set.remove(member).is_some() as i64),
+            Some(_) => Err(wrong_type()),
+            None => Ok(0),
+        })
+    }
+
+    fn publish(&self, stream: &str, fields: &[(&str, String)]) -> RedisResult<String> {
+        logger.debug_single(&format!("Publishing event to stream {}", stream), "Memory");
+        self.with_entry_or(stream, Value::Stream(VecDeque::new()), |entry| match &mut entry.value {
+            Value::Stream(events) => {
+                let millis = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+                let id = format!("{}-{}", millis, self.sequence.fetch_add(1, Ordering::Relaxed));
+                events.push_back((id.clone(), fields.iter().map(|(field, value)| (field.to_string(), value.clone())).collect()));
+                while events.len() > STREAM_LENGTH {
+                    events.pop_front();
+                }
+                Ok(id)
+            },
+            _ => Err(wrong_type()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_match_wildcards() {
+        assert!(glob_match("media:*", "media:1"));
+        assert!(glob_match("media:*", "media:"));
+        assert!(glob_match("media:?", "media:1"));
+        assert!(!glob_match("media:?", "media:12"));
+        assert!(glob_match("*:list:*", "user:someone:list:ANIME"));
+        assert!(!glob_match("user:*", "media:1"));
+    }
+
+    #[test]
+    fn glob_match_escapes() {
+        assert!(glob_match("media:\\*", "media:*"));
+        assert!(!glob_match("media:\\*", "media:1"));
+        assert!(glob_match("media:\\?:*", "media:?:characters"));
+        assert!(!glob_match("media:\\?", "media:1"));
+    }
+
+    #[test]
+    fn ttl_without_expiry_or_key() {
+        let memory = Memory::new();
+        memory.set("media:1", "{}").unwrap();
+        assert_eq!(memory.ttl("media:1").unwrap(), -1);
+        assert_eq!(memory.ttl("media:2").unwrap(), -2);
+
+        memory.expire("media:1", 60).unwrap();
+        assert!((0..=60).contains(&memory.ttl("media:1").unwrap()));
+    }
+
+    #[test]
+    fn expire_without_time_left_removes_key() {
+        let memory = Memory::new();
+        memory.set("media:1", "{}").unwrap();
+        memory.set("media:2", "{}").unwrap();
+        memory.expire("media:1", 0).unwrap();
+        memory.expire("media:2", -5).unwrap();
+
+        assert!(memory.get("media:1").is_err());
+        assert_eq!(memory.ttl("media:2").unwrap(), -2);
+        assert_eq!(memory.delete("media:2").unwrap(), 0);
+    }
+
+    #[test]
+    fn set_sweeps_expired_keys() {
+        let memory = Memory::new();
+        memory.set("media:1", "{}").unwrap();
+        memory.entries.lock().unwrap().get_mut("media:1").unwrap().expires_at = Some(Instant::now());
+        *memory.swept_at.lock().unwrap() = Instant::now() - SWEEP_INTERVAL;
+
+        memory.set("media:2", "{}").unwrap();
+        assert!(!memory.entries.lock().unwrap().contains_key("media:1"));
+    }
+
+    #[test]
+    fn due_is_ordered_by_score() {
+        let memory = Memory::new();
+        memory.schedule("airing", "third", 30).unwrap();
+        memory.schedule("airing", "first", 10).unwrap();
+        memory.schedule("airing", "second", 20).unwrap();
+        memory.schedule("airing", "later", 50).unwrap();
+
+        assert_eq!(memory.due("airing", 30).unwrap(), vec!["first", "second", "third"]);
+        assert!(memory.due("airing", 5).unwrap().is_empty());
+    }
+
+    #[test]
+    fn hash_take_removes_field() {
+        let memory = Memory::new();
+        memory.hash_set("proxies", "one", "http://one").unwrap();
+
+        assert_eq!(memory.hash_take("proxies", "one").unwrap(), Some("http://one".to_string()));
+        assert_eq!(memory.hash_take("proxies", "one").unwrap(), None);
+        assert_eq!(memory.hash_take("missing", "one").unwrap(), None);
+        assert!(memory.hash_get_all("proxies").unwrap().is_empty());
+    }
+
+    #[test]
+    fn wrong_type_is_an_error() {
+        let memory = Memory::new();
+        memory.set("media:1", "{}").unwrap();
+        assert!(memory.hash_take("media:1", "field").is_err());
+    }
+}
@@ -1,2 +1,9 @@
+pub mod backend;
 pub mod redis;
-pub mod proxy;
\ No newline at end of file
+pub mod memory;
+pub mod proxy;
+pub mod warmup;
+pub mod keys;
+pub mod admin;
+pub mod airing;
+pub mod negative;
\ No newline at end of file
@@ -0,0 +1,38 @@
+use std::env;
+use colourful_logger::Logger;
+use lazy_static::lazy_static;
+use crate::cache::backend::cache;
+use crate::cache::keys::not_found_key;
+
+lazy_static! {
+    static ref logger: Logger = Logger::default();
+}
+
+fn not_found_ttl() -> i64 {
+    env::var("NEGATIVE_CACHE_TTL").ok().and_then(|ttl| ttl.parse::<i64>().ok()).unwrap_or(300)
+}
+
+/// Returns how long is left on the tombstone for `redis_key`, if Anilist recently reported it as not found.
+pub fn cached_not_found(redis_key: &str) -> Option<i64> {
+    let tombstone = not_found_key(redis_key);
+    cache.get(&tombstone).ok()?;
+    cache.ttl(&tombstone).ok()
+}
+
+pub fn cache_not_found(redis_key: &str) {
+    let ttl = not_found_ttl();
+    if ttl <= 0 {
+        return;
+    }
+
+    logger.debug_single(&format!("Caching {} as not found for {} seconds", redis_key, ttl), "Negative Cache");
+    let tombstone = not_found_key(redis_key);
+    let _ = cache.set(&tombstone, "{\"notFound\":true}");
+    let _ = cache.expire(&tombstone, ttl);
+}
+
+/// Removes both the cached entry and its tombstone, so the next lookup goes to Anilist.
+pub fn clear_cached(redis_key: &str) -> i64 {
+    logger.debug_single(&format!("Clearing cached data for {}", redis_key), "Negative Cache");
+    cache.delete(redis_key).unwrap_or(0) + cache.delete(&not_found_key(redis_key)).unwrap_or(0)
+}
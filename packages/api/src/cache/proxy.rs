@@ -1,8 +1,9 @@
 use std::error::Error;
 use colourful_logger::Logger;
+use rand::Rng;
 use reqwest::{Client, StatusCode};
-use redis::{Client as RedisClient, Commands};
 use lazy_static::lazy_static;
+use crate::cache::backend::cache;
 
 lazy_static! {
     static ref logger: Logger = Logger::default();
@@ -31,36 +32,33 @@ async fn fetch_proxies(url: &String) -> Result<Vec<String>, Box<dyn Error>> {
     Ok(proxy_vec)
 }
 
-pub async fn get_random_proxy(redis_client: &RedisClient) -> Result<String, Box<dyn Error>> {
+pub async fn get_random_proxy() -> Result<String, Box<dyn Error>> {
     logger.debug_single("Getting random proxy", "Proxy");
-    let mut con = redis_client.get_connection()?;
-    let proxy: std::collections::HashMap<String, String> = con.hgetall("proxies")?;
+    let proxy = cache.hash_get_all("proxies")?;
 
     if proxy.is_empty() {
         logger.error("Failed to find a proxy", "Proxy", false, proxy.len().to_string());
         return Err("No proxies found".into());
     }
 
-    let proxy_value = proxy.iter().next().unwrap().1;
-    logger.debug("Returning first proxy in redis", "Proxy", false, proxy_value.clone());
+    let proxy_value = &proxy[rand::rng().random_range(0..proxy.len())].1;
+    logger.debug("Returning random proxy in redis", "Proxy", false, proxy_value.clone());
     Ok(proxy_value.to_string())
 }
 
-pub async fn remove_proxy(redis_client: &RedisClient, proxy: &str) -> Result<(), Box<dyn Error>> {
+pub async fn remove_proxy(proxy: &str) -> Result<(), Box<dyn Error>> {
     logger.debug_single(&format!("Removing proxy: {}", proxy), "Proxy");
-    let mut con = redis_client.get_connection()?;
-    let _: () = con.hdel("proxies", proxy)?;
+    cache.hash_delete("proxies", proxy)?;
     Ok(())
 }
 
-async fn remove_all_proxies(redis_client: &RedisClient) -> Result<(), Box<dyn Error>> {
+async fn remove_all_proxies() -> Result<(), Box<dyn Error>> {
     logger.debug_single("Removing all proxies", "Proxy");
-    let mut con = redis_client.get_connection()?;
-    let _: () = con.del("proxies")?;
+    cache.delete("proxies")?;
     Ok(())
 }
 
-pub async fn update_proxy_list(redis_client: &RedisClient, url: &String) -> Result<(), Box<dyn Error>> {
+pub async fn update_proxy_list(url: &String) -> Result<(), Box<dyn Error>> {
     loop {
         logger.debug_single("Updating proxy list", "Proxy");
         let proxies = fetch_proxies(url).await?;
@@ -73,16 +71,14 @@ pub async fn update_proxy_list(redis_client: &RedisClient, url: &String) -> Resu
             },
             _ => {
                 logger.debug_single(&format!("Found {} proxies", proxies.len()), "Proxies");
-                let _: () = remove_all_proxies(redis_client).await?;
+                let _: () = remove_all_proxies().await?;
             }
         }
-        let mut con = redis_client.get_connection()?;
-
         logger.debug_single("Updating redis with new proxies", "Proxies");
         for (index, proxy) in proxies.iter().enumerate() {
             let key = format!("proxy:{}", index);
             let value = format!("{}", proxy);
-            let _: () = con.hset("proxies", key, value)?;
+            let _: () = cache.hash_set("proxies", &key, &value)?;
         }
         tokio::time::sleep(tokio::time::Duration::from_secs(18000)).await; // 18000 seconds = 5 hours
     }
@@ -1,7 +1,8 @@
 use std::env;
-use redis::{Client, ToRedisArgs, RedisResult, Commands};
+use redis::{Client, RedisResult, Commands};
 use colourful_logger::Logger as Logger;
 use lazy_static::lazy_static;
+use crate::cache::backend::CacheBackend;
 
 lazy_static! {
     static ref logger: Logger = Logger::default();
@@ -19,12 +20,14 @@ impl Redis {
             client: Client::open(redis_url).unwrap(),
         }
     }
+}
 
-    pub fn get_client(&self) -> Client {
-        self.client.clone()
+impl CacheBackend for Redis {
+    fn name(&self) -> &'static str {
+        "redis"
     }
 
-    pub fn get<T: ToRedisArgs + std::fmt::Debug>(&self, key: T) -> RedisResult<String> {
+    fn get(&self, key: &str) -> RedisResult<String> {
         logger.debug_single(&format!("Trying to grab key : {:?}", key), "Redis");
         let mut con = self.client.get_connection()?;
         let rv: Option<String> = con.get(key)?;
@@ -44,7 +47,7 @@ impl Redis {
         }
     }
 
-    pub fn set<T: ToRedisArgs + std::fmt::Debug, V: ToRedisArgs + std::fmt::Debug>(&self, key: T, value: V) -> RedisResult<()> {
+    fn set(&self, key: &str, value: &str) -> RedisResult<()> {
         logger.debug_single(&format!("Setting Key with data {:?}", key).as_str(), "Redis");
         let mut con = self.client.get_connection()?;
         
@@ -61,7 +64,7 @@ impl Redis {
         }
     }
 
-    pub fn expire<T: ToRedisArgs + std::fmt::Debug>(&self, key: T, seconds: i64) -> RedisResult<()> {
+    fn expire(&self, key: &str, seconds: i64) -> RedisResult<()> {
         logger.debug_single(&format!("Setting Key to expire in {} seconds : {:?}", seconds, key).as_str(), "Redis");
         let mut con = self.client.get_connection()?;
         let result: RedisResult<()> = con.expire(key, seconds);
@@ -78,7 +81,7 @@ impl Redis {
         }
     }
 
-    pub fn ttl<T: ToRedisArgs + std::fmt::Debug>(&self, key: T) -> RedisResult<i64> {
+    fn ttl(&self, key: &str) -> RedisResult<i64> {
         logger.debug_single(&format!("Getting TTL for key : {:?}", key).as_str(), "Redis");
         let mut con = self.client.get_connection()?;
         let result: RedisResult<i64> = con.ttl(key);
@@ -95,29 +98,84 @@ impl Redis {
         }
     }
 
-    pub async fn expire_user<T: ToRedisArgs + std::fmt::Debug + std::fmt::Display>(&self, user_id: T) -> RedisResult<()> {
-        logger.debug_single(&format!("Deleting all cached related for user ID {:?}", user_id).as_str(), "Redis");
+    fn delete(&self, key: &str) -> RedisResult<i64> {
+        logger.debug_single(&format!("Deleting key : {:?}", key), "Redis");
         let mut con = self.client.get_connection()?;
-    
-        let mut count = 0;
-        let iter: redis::Iter<String> = con.scan()?;
-        let keys: Vec<String> = iter.collect();
-        for key in keys {
-            let parts: Vec<&str> = key.split(":").collect();
-            logger.debug_single(&format!("Split up parts: {:?}", &parts), "Redis");
+        let result: RedisResult<i64> = con.del(key);
 
-            if parts.get(1) == Some(&user_id.to_string().as_str()) {
-                logger.debug_single(&format!("Found Key: {:?}", key), "Redis");
-                let _: () = con.del(key)?;
-                count += 1;
-            }
+        match &result {
+            Ok(count) => logger.debug_single(&format!("Deleted {} key(s)", count), "Redis"),
+            Err(e) => logger.error_single(&format!("Error deleting key : {:?}", e), "Redis"),
         }
+        result
+    }
 
-        if count == 0 {
-            logger.warn_single("No keys found for user ID", "Redis");
-            return Err(redis::RedisError::from((redis::ErrorKind::ResponseError, "No keys found for user ID")));
+    fn scan_keys(&self, pattern: &str) -> RedisResult<Vec<String>> {
+        logger.debug_single(&format!("Scanning keys matching : {}", pattern), "Redis");
+        let mut con = self.client.get_connection()?;
+        let iter: redis::Iter<String> = con.scan_match(pattern)?;
+        Ok(iter.collect())
+    }
+
+    fn memory_usage(&self, keys: &[String]) -> RedisResult<Vec<Option<i64>>> {
+        logger.debug_single(&format!("Getting memory usage for {} key(s)", keys.len()), "Redis");
+        let mut con = self.client.get_connection()?;
+        let mut pipe = redis::pipe();
+        for key in keys {
+            pipe.cmd("MEMORY").arg("USAGE").arg(key);
         }
+        pipe.query(&mut con)
+    }
+
+    fn hash_set(&self, key: &str, field: &str, value: &str) -> RedisResult<()> {
+        let mut con = self.client.get_connection()?;
+        con.hset(key, field, value)
+    }
 
-        Ok(())
+    fn hash_get_all(&self, key: &str) -> RedisResult<Vec<(String, String)>> {
+        let mut con = self.client.get_connection()?;
+        con.hgetall(key)
+    }
+
+    fn hash_take(&self, key: &str, field: &str) -> RedisResult<Option<String>> {
+        let mut con = self.client.get_connection()?;
+        let value: Option<String> = con.hget(key, field)?;
+        let _: () = con.hdel(key, field)?;
+        Ok(value)
     }
-}
\ No newline at end of file
+
+    fn hash_delete(&self, key: &str, field: &str) -> RedisResult<()> {
+        let mut con = self.client.get_connection()?;
+        con.hdel(key, field)
+    }
+
+    fn schedule(&self, key: &str, member: &str, score: i64) -> RedisResult<()> {
+        logger.debug_single(&format!("Scheduling {:?} in {} at {}", member, key, score), "Redis");
+        let mut con = self.client.get_connection()?;
+        con.zadd(key, member, score)
+    }
+
+    fn due(&self, key: &str, until: i64) -> RedisResult<Vec<String>> {
+        let mut con = self.client.get_connection()?;
+        con.zrangebyscore(key, "-inf", until)
+    }
+
+    fn unschedule(&self, key: &str, member: &str) -> RedisResult<i64> {
+        logger.debug_single(&format!("Unscheduling {:?} from {}", member, key), "Redis");
+        let mut con = self.client.get_connection()?;
+        con.zrem(key, member)
+    }
+
+    fn publish(&self, stream: &str, fields: &[(&str, String)]) -> RedisResult<String> {
+        logger.debug_single(&format!("Publishing event to stream {}", stream), "Redis");
+        let mut con = self.client.get_connection()?;
+        redis::cmd("XADD")
+            .arg(stream)
+            .arg("MAXLEN")
+            .arg("~")
+            .arg(1000)
+            .arg("*")
+            .arg(fields)
+            .query(&mut con)
+    }
+}
@@ -0,0 +1,112 @@
+use std::env;
+use std::error::Error;
+use reqwest::{Client, Response};
+use serde_json::json;
+use colourful_logger::Logger;
+use lazy_static::lazy_static;
+use crate::anilist::queries::{get_query, QUERY_URL};
+use crate::anilist::media::{cache_media, wash_media_node};
+use crate::cache::backend::cache;
+use crate::cache::proxy::{get_random_proxy, remove_proxy};
+use crate::global::rate_budget::wait_for_budget;
+use crate::global::season::current_season;
+
+lazy_static! {
+    static ref logger: Logger = Logger::default();
+}
+
+const RETRY_DELAY: u64 = 60;
+
+fn env_or<T: std::str::FromStr>(variable: &str, default: T) -> T {
+    env::var(variable).ok().and_then(|value| value.parse::<T>().ok()).unwrap_or(default)
+}
+
+pub async fn warm_media_cache() {
+    let interval: u64 = env_or("WARMUP_INTERVAL", 3600);
+    let pages: i64 = env_or("WARMUP_PAGES", 3);
+
+    if interval == 0 || pages == 0 {
+        logger.info_single("Cache warm-up is disabled", "Warmup");
+        return;
+    }
+
+    // The proxy list is fetched at the same time on startup, warming before it is stored would only fail
+    while cache.hash_get_all("proxies").map(|proxies| proxies.is_empty()).unwrap_or(true) {
+        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+    }
+
+    loop {
+        let (season, year) = current_season();
+        let passes = [
+            ("airing", json!({"season": season, "seasonYear": year, "status": "RELEASING", "type": "ANIME", "sort": ["POPULARITY_DESC"]})),
+            ("trending", json!({"sort": ["TRENDING_DESC"]})),
+        ];
+
+        let mut failed = false;
+        for (label, variables) in passes {
+            match warm_pass(variables, pages).await {
+                Ok(count) => logger.info_single(&format!("Warmed {} {} media entries", count, label), "Warmup"),
+                Err(e) => {
+                    logger.error_single(&format!("Failed to warm {} media: {:?}", label, e), "Warmup");
+                    failed = true;
+                }
+            }
+        }
+
+        // A failed pass is retried shortly rather than leaving the cache cold for a whole interval
+        let delay = if failed { RETRY_DELAY.min(interval) } else { interval };
+        tokio::time::sleep(tokio::time::Duration::from_secs(delay)).await;
+    }
+}
+
+async fn warm_pass(variables: serde_json::Value, pages: i64) -> Result<usize, Box<dyn Error>> {
+    let mut count = 0;
+
+    for page in 1..=pages {
+        wait_for_budget().await;
+        let media_page = fetch_media_page(&variables, page).await?;
+        let data = &media_page["data"]["Page"];
+
+        for media in data["media"].as_array().unwrap_or(&vec![]) {
+            let mut media = wash_media_node(media.clone()).await;
+            media["dataFrom"] = "Warmup".into();
+            cache_media(&media);
+            count += 1;
+        }
+
+        if !data["pageInfo"]["hasNextPage"].as_bool().unwrap_or(false) {
+            break;
+        }
+    }
+
+    Ok(count)
+}
+
+async fn fetch_media_page(variables: &serde_json::Value, page: i64) -> Result<serde_json::Value, Box<dyn Error>> {
+    let get_proxy = get_random_proxy().await?;
+    let proxy = reqwest::Proxy::http(get_proxy.clone())?;
+    let client = Client::builder().proxy(proxy).build()?;
+
+    let mut variables = variables.clone();
+    variables["page"] = page.into();
+    variables["perPage"] = 50.into();
+
+    let json = json!({"query": get_query("airing_warmup"), "variables": variables});
+    logger.debug_single(&format!("Fetching warm-up page {}", page), "Warmup");
+
+    let response: Response = client
+            .post(QUERY_URL)
+            .json(&json)
+            .send()
+            .await?;
+
+    if response.status().as_u16() != 200 {
+        if response.status().as_u16() == 403 {
+            let _ = remove_proxy(get_proxy.as_str()).await;
+        }
+
+        return Err(format!("Request returned {}", response.status().as_str()).into());
+    }
+
+    Ok(response.json::<serde_json::Value>().await?)
+}
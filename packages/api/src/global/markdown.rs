@@ -0,0 +1,262 @@
+#[derive(Clone, Copy, PartialEq)]
+pub enum Spoilers {
+    Strip,
+    Wrap,
+}
+
+const MARKERS: [&str; 4] = ["||", "**", "__", "~~"];
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest.find(';').filter(|end| *end <= 10) else {
+            decoded.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+
+        let entity = &rest[1..end];
+        let character = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            "mdash" => Some('—'),
+            "ndash" => Some('–'),
+            "hellip" => Some('…'),
+            _ => entity.strip_prefix('#').and_then(|code| {
+                match code.strip_prefix('x').or_else(|| code.strip_prefix('X')) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => code.parse::<u32>().ok(),
+                }
+            }).and_then(char::from_u32),
+        };
+
+        match character {
+            Some(character) => {
+                decoded.push(character);
+                rest = &rest[end + 1..];
+            },
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Handles Anilist's `~!spoiler!~` syntax, either removing the section or wrapping it in Discord spoiler tags.
+fn replace_spoilers(text: &str, spoilers: Spoilers) -> String {
+    let mut replaced = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("~!") {
+        let Some(length) = rest[start + 2..].find("!~") else {
+            break;
+        };
+
+        replaced.push_str(&rest[..start]);
+        let spoiler = rest[start + 2..start + 2 + length].trim();
+        if spoilers == Spoilers::Wrap && !spoiler.is_empty() {
+            replaced.push_str(&format!("||{}||", spoiler));
+        }
+        rest = &rest[start + 2 + length + 2..];
+    }
+
+    replaced.push_str(rest);
+    replaced
+}
+
+fn href(tag: &str) -> Option<&str> {
+    let start = tag.find("href=")? + 5;
+    let value = &tag[start..];
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let value = &value[1..];
+    value.find(quote).map(|end| &value[..end])
+}
+
+/// Converts the limited HTML Anilist uses in descriptions into Discord flavoured markdown.
+pub fn html_to_markdown(html: &str, spoilers: Spoilers) -> String {
+    let html = replace_spoilers(html, spoilers);
+    let mut markdown = String::with_capacity(html.len());
+    let mut links: Vec<Option<String>> = vec![];
+    let mut rest = html.as_str();
+
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+
+        markdown.push_str(&rest[..start]);
+        let tag = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+
+        match name.as_str() {
+            "br" => markdown.push('\n'),
+            "p" | "div" => markdown.push_str("\n\n"),
+            "i" | "em" => markdown.push('*'),
+            "b" | "strong" => markdown.push_str("**"),
+            "u" => markdown.push_str("__"),
+            "s" | "del" | "strike" => markdown.push_str("~~"),
+            "a" if !closing => {
+                let link = href(tag).map(|link| link.to_string());
+                if link.is_some() {
+                    markdown.push('[');
+                }
+                links.push(link);
+            },
+            "a" => {
+                if let Some(Some(link)) = links.pop() {
+                    markdown.push_str(&format!("]({})", link));
+                }
+            },
+            _ => {}
+        }
+    }
+
+    markdown.push_str(rest);
+    let markdown = decode_entities(&markdown).replace("\r\n", "\n");
+
+    let mut collapsed = String::with_capacity(markdown.len());
+    let mut newlines = 0;
+    for character in markdown.trim().chars() {
+        if character == ' ' && collapsed.ends_with(' ') {
+            continue;
+        }
+
+        if character == '\n' {
+            newlines += 1;
+            if newlines > 2 {
+                continue;
+            }
+        } else {
+            newlines = 0;
+        }
+        collapsed.push(character);
+    }
+
+    collapsed.lines().map(|line| line.trim_end()).collect::<Vec<_>>().join("\n")
+}
+
+/// Closes any markdown markers left open after a truncation.
+fn balance_markers(text: &mut String) {
+    for marker in MARKERS {
+        if text.matches(marker).count() % 2 == 1 {
+            text.push_str(marker);
+        }
+    }
+
+    if text.replace("**", "").matches('*').count() % 2 == 1 {
+        text.push('*');
+    }
+}
+
+/// Shortens text to at most `max_length` characters, cutting at the last full sentence where possible.
+pub fn truncate_sentences(text: &str, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        return text.to_string();
+    }
+
+    let cut: String = text.chars().take(max_length).collect();
+    let sentence_end = cut
+        .char_indices()
+        .filter(|(index, character)| {
+            matches!(character, '.' | '!' | '?')
+                && text[index + character.len_utf8()..].chars().next().is_none_or(|next| next.is_whitespace())
+        })
+        .map(|(index, character)| index + character.len_utf8())
+        .next_back();
+
+    let mut truncated = match sentence_end {
+        Some(end) => cut[..end].to_string(),
+        None => {
+            let end = cut.rfind(char::is_whitespace).unwrap_or(cut.len());
+            format!("{}…", cut[..end].trim_end())
+        }
+    };
+
+    balance_markers(&mut truncated);
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spoilers_are_stripped() {
+        let markdown = html_to_markdown("Before ~!the twist!~ after", Spoilers::Strip);
+        assert_eq!(markdown, "Before after");
+    }
+
+    #[test]
+    fn spoilers_are_wrapped() {
+        let markdown = html_to_markdown("Before ~! the twist !~ after", Spoilers::Wrap);
+        assert_eq!(markdown, "Before ||the twist|| after");
+    }
+
+    #[test]
+    fn unclosed_spoiler_is_kept() {
+        let markdown = html_to_markdown("Before ~!the twist", Spoilers::Strip);
+        assert_eq!(markdown, "Before ~!the twist");
+    }
+
+    #[test]
+    fn nested_tags() {
+        let markdown = html_to_markdown("<b><i>Both</i></b> and <a href=\"https://anilist.co\">link</a><br>next", Spoilers::Strip);
+        assert_eq!(markdown, "***Both*** and [link](https://anilist.co)\nnext");
+    }
+
+    #[test]
+    fn entities_are_decoded() {
+        let markdown = html_to_markdown("It&#39;s Tom &amp; Jerry &#x2014; &lt;3 &unknown; & more", Spoilers::Strip);
+        assert_eq!(markdown, "It's Tom & Jerry — <3 &unknown; & more");
+    }
+
+    #[test]
+    fn short_text_is_untouched() {
+        assert_eq!(truncate_sentences("Short text.", 20), "Short text.");
+    }
+
+    #[test]
+    fn truncates_at_last_sentence() {
+        assert_eq!(truncate_sentences("First one. Second one. Third one.", 25), "First one. Second one.");
+    }
+
+    #[test]
+    fn truncates_multi_byte_characters() {
+        assert_eq!(truncate_sentences("日本語のテキスト。続きの文章です", 10), "日本語のテキスト。続…");
+        assert_eq!(truncate_sentences("Café crème. Très bien, très très bien.", 20), "Café crème.");
+    }
+
+    #[test]
+    fn closes_markers_cut_off() {
+        assert_eq!(truncate_sentences("Intro **bold words that go on", 20), "Intro **bold words…**");
+        assert_eq!(truncate_sentences("Intro ||hidden spoiler text|| end", 22), "Intro ||hidden…||");
+        assert_eq!(truncate_sentences("Intro *leaning words that go on", 20), "Intro *leaning…*");
+    }
+
+    #[test]
+    fn cut_inside_marker() {
+        assert_eq!(truncate_sentences("Some words **bold**", 12), "Some words…");
+        assert_eq!(truncate_sentences("Some words ||hidden||", 12), "Some words…");
+    }
+}
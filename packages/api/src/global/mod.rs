@@ -1 +1,5 @@
-pub mod compare_strings;
\ No newline at end of file
+pub mod compare_strings;
+pub mod rate_budget;
+pub mod season;
+pub mod markdown;
+pub mod statistics;
\ No newline at end of file
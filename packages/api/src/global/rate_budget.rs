@@ -0,0 +1,79 @@
+use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use lazy_static::lazy_static;
+
+const DEFAULT_RATE_LIMIT: f64 = 90.0; // Anilist allows 90 requests per minute
+
+lazy_static! {
+    /// Shared by every job that sends requests in bulk (warm-up, leaderboards, franchises, reviews, recommendations), so together they stay within one share.
+    static ref shared_budget: Mutex<RateBudget> = Mutex::new(RateBudget::from_env("BATCH_RATE_SHARE", 0.5));
+}
+
+struct RateBudget {
+    interval: Duration,
+    next_slot: Option<Instant>,
+}
+
+impl RateBudget {
+    /// Spaces requests so they only use `share` (0.0 - 1.0) of the upstream rate limit.
+    fn new(share: f64) -> Self {
+        let rate_limit = env::var("ANILIST_RATE_LIMIT")
+            .ok()
+            .and_then(|limit| limit.parse::<f64>().ok())
+            .filter(|limit| *limit > 0.0)
+            .unwrap_or(DEFAULT_RATE_LIMIT);
+        let share = share.clamp(0.01, 1.0);
+
+        RateBudget {
+            interval: Duration::from_secs_f64(60.0 / (rate_limit * share)),
+            next_slot: None,
+        }
+    }
+
+    fn from_env(variable: &str, default_share: f64) -> Self {
+        let share = env::var(variable)
+            .ok()
+            .and_then(|share| share.parse::<f64>().ok())
+            .unwrap_or(default_share);
+        RateBudget::new(share)
+    }
+
+    /// Takes the next free slot, returning how long until it starts.
+    fn reserve(&mut self) -> Duration {
+        let now = Instant::now();
+        let slot = self.next_slot.map_or(now, |next_slot| next_slot.max(now));
+        self.next_slot = Some(slot + self.interval);
+        slot - now
+    }
+}
+
+/// Waits for a slot in the shared budget, the lock is only held while reserving so callers queue up in order.
+pub async fn wait_for_budget() {
+    let delay = shared_budget.lock().unwrap().reserve();
+    if !delay.is_zero() {
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Roughly how long a request queued now would wait for its slot.
+pub fn budget_delay() -> Duration {
+    let budget = shared_budget.lock().unwrap();
+    budget.next_slot.map_or(Duration::ZERO, |next_slot| next_slot.saturating_duration_since(Instant::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slots_are_spaced_by_the_interval() {
+        let mut budget = RateBudget { interval: Duration::from_secs(2), next_slot: None };
+        assert!(budget.reserve().is_zero());
+
+        let second = budget.reserve();
+        let third = budget.reserve();
+        assert!(second > Duration::from_millis(1900) && second <= Duration::from_secs(2));
+        assert!(third > Duration::from_millis(3900) && third <= Duration::from_secs(4));
+    }
+}
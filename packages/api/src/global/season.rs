@@ -0,0 +1,55 @@
+use chrono::{Datelike, NaiveDate, Utc};
+
+pub const SEASONS: [&str; 4] = ["WINTER", "SPRING", "SUMMER", "FALL"];
+
+/// Seasons follow the broadcast quarters, starting in January, April, July and October, the same months `season_start` uses.
+pub fn season_for_date(date: NaiveDate) -> (&'static str, i32) {
+    match date.month() {
+        1..=3 => ("WINTER", date.year()),
+        4..=6 => ("SPRING", date.year()),
+        7..=9 => ("SUMMER", date.year()),
+        _ => ("FALL", date.year()),
+    }
+}
+
+pub fn current_season() -> (&'static str, i32) {
+    season_for_date(Utc::now().date_naive())
+}
+
+/// Anilist's fuzzy date (`YYYYMMDD`) for the first month of a season, used for date range filters.
+pub fn season_start(season: &str, year: i32) -> Option<i64> {
+    let month = match season {
+        "WINTER" => 1,
+        "SPRING" => 4,
+        "SUMMER" => 7,
+        "FALL" => 10,
+        _ => return None,
+    };
+    Some(year as i64 * 10000 + month * 100)
+}
+
+/// Sortable `YYYYMMDD` for an Anilist start date, unknown months and days sort to the end of the year or month.
+pub fn sortable_date(date: &serde_json::Value) -> Option<i64> {
+    date["year"].as_i64().map(|year| {
+        year * 10000 + date["month"].as_i64().unwrap_or(12) * 100 + date["day"].as_i64().unwrap_or(31)
+    })
+}
+
+/// Orders seasons chronologically, so seasons can be compared against the current one.
+pub fn season_index(season: &str, year: i32) -> Option<i32> {
+    SEASONS.iter().position(|s| *s == season).map(|position| year * 4 + position as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn sortable_dates() {
+        assert_eq!(sortable_date(&json!({"year": 2024, "month": 4, "day": 6})), Some(20240406));
+        assert_eq!(sortable_date(&json!({"year": 2024, "month": 4, "day": null})), Some(20240431));
+        assert_eq!(sortable_date(&json!({"year": 2024, "month": null, "day": null})), Some(20241231));
+        assert_eq!(sortable_date(&json!({"year": null, "month": 4, "day": 6})), None);
+    }
+}
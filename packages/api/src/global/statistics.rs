@@ -0,0 +1,61 @@
+pub fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Pearson correlation coefficient of paired values, `None` with fewer than two pairs or when either side never varies.
+pub fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < 2 {
+        return None;
+    }
+
+    let mean_x = mean(&pairs.iter().map(|(x, _)| *x).collect::<Vec<f64>>())?;
+    let mean_y = mean(&pairs.iter().map(|(_, y)| *y).collect::<Vec<f64>>())?;
+
+    let (mut covariance, mut variance_x, mut variance_y) = (0.0, 0.0, 0.0);
+    for (x, y) in pairs {
+        covariance += (x - mean_x) * (y - mean_y);
+        variance_x += (x - mean_x).powi(2);
+        variance_y += (y - mean_y).powi(2);
+    }
+
+    if variance_x == 0.0 || variance_y == 0.0 {
+        return None;
+    }
+
+    Some(covariance / (variance_x.sqrt() * variance_y.sqrt()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mean_of_nothing() {
+        assert_eq!(mean(&[]), None);
+        assert_eq!(mean(&[1.0, 2.0, 6.0]), Some(3.0));
+    }
+
+    #[test]
+    fn pearson_needs_two_pairs() {
+        assert_eq!(pearson(&[]), None);
+        assert_eq!(pearson(&[(1.0, 2.0)]), None);
+    }
+
+    #[test]
+    fn pearson_without_variance() {
+        assert_eq!(pearson(&[(80.0, 10.0), (80.0, 50.0), (80.0, 90.0)]), None);
+        assert_eq!(pearson(&[(10.0, 70.0), (50.0, 70.0)]), None);
+    }
+
+    #[test]
+    fn pearson_known_values() {
+        let correlation = pearson(&[(1.0, 2.0), (2.0, 4.0), (3.0, 5.0), (4.0, 4.0), (5.0, 5.0)]).unwrap();
+        assert!((correlation - 0.6_f64.sqrt()).abs() < 1e-9);
+
+        assert!((pearson(&[(1.0, 10.0), (2.0, 20.0), (3.0, 30.0)]).unwrap() - 1.0).abs() < 1e-9);
+        assert!((pearson(&[(1.0, 30.0), (2.0, 20.0), (3.0, 10.0)]).unwrap() + 1.0).abs() < 1e-9);
+    }
+}
@@ -8,14 +8,25 @@ extern crate num_cpus;
 mod anilist;
 mod cache;
 mod global;
-use anilist::media::{media_search, relations_search, recommend};
+use anilist::media::{media_search, media_batch, relations_search, recommend};
 use anilist::user::{user_search, user_score, expire};
-use cache::redis::Redis;
+use anilist::characters::{media_characters, media_staff};
+use anilist::studio::{studio_search, studio_details};
+use anilist::schedule::airing_schedule;
+use anilist::season::{current_season_chart, season_search};
+use anilist::rankings::{trending, popular};
+use anilist::franchise::media_franchise;
+use anilist::reviews::media_reviews;
+use anilist::search::media_filter_search;
+use anilist::recommendations::{media_recommendations, user_recommendations};
+use anilist::affinity::{user_affinity, affinity_leaderboard};
 use cache::proxy::update_proxy_list;
+use cache::warmup::warm_media_cache;
+use cache::airing::publish_aired_episodes;
+use cache::admin::{cache_lookup, cache_purge, cache_purge_type, cache_stats};
 
 lazy_static! {
     static ref logger: Logger = Logger::default();
-    static ref redis:  Redis  = Redis::new();
 }
 
 #[get("/")]
@@ -32,6 +43,7 @@ async fn main() -> std::io::Result<()> {
     dotenvy::dotenv().unwrap_or_default();
 
     logger.info_single("Starting Anilist API Proxy", "Main");
+    lazy_static::initialize(&cache::backend::cache);
     let ip = env::var("API_HOST").unwrap_or("0.0.0.0".to_string());
     let port = env::var("API_PORT").unwrap().parse::<u16>().unwrap_or(8080);
     let check_proxy = env::var("API_PROXY").map_err(|_| {
@@ -48,7 +60,7 @@ async fn main() -> std::io::Result<()> {
     tokio::spawn(async move {
         let mut attempts: u8 = 0;
         while attempts < 10 {
-            if let Err(e) = update_proxy_list(&redis.get_client(), &check_proxy).await {
+            if let Err(e) = update_proxy_list(&check_proxy).await {
                 logger.error_single(&format!("Failed to update proxy list (attempt {}): {:?}", attempts + 1, e), "Main");
                 thread::sleep(std::time::Duration::from_secs(5));
                 attempts += 1;
@@ -58,6 +70,8 @@ async fn main() -> std::io::Result<()> {
             logger.error_single("Failed to update proxy list after 10 attempts", "Main");
         }
     });
+    tokio::spawn(warm_media_cache());
+    tokio::spawn(publish_aired_episodes());
     
     HttpServer::new(move || {
         App::new()
@@ -65,9 +79,30 @@ async fn main() -> std::io::Result<()> {
             .service(user_search)
             .service(user_score)
             .service(media_search)
+            .service(media_batch)
             .service(relations_search)
             .service(expire)
             .service(recommend)
+            .service(media_characters)
+            .service(media_staff)
+            .service(studio_search)
+            .service(studio_details)
+            .service(airing_schedule)
+            .service(current_season_chart)
+            .service(season_search)
+            .service(trending)
+            .service(popular)
+            .service(media_franchise)
+            .service(media_reviews)
+            .service(media_filter_search)
+            .service(media_recommendations)
+            .service(user_recommendations)
+            .service(user_affinity)
+            .service(affinity_leaderboard)
+            .service(cache_lookup)
+            .service(cache_purge)
+            .service(cache_purge_type)
+            .service(cache_stats)
             .route("/hey", web::get().to(manual))
     })
     .workers(num_cpus::get())
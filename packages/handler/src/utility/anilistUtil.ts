@@ -31,6 +31,15 @@ export function intervalTime(seconds: number, granularity = 2): string {
     return result.slice(0, granularity).join(", ");
 }
 
+// Media cached before the API returned labelled statuses and dates still holds plain strings
+function displayField(value: any, field: string): string {
+    if (typeof value === "string") {
+        return value;
+    }
+
+    return value?.[field] ?? "?";
+}
+
 export async function fetchAnilistMedia(mediaType: string, mediaID: number, interaction: any): Promise<any> {
     const response = await fetch(`${env.API_URL}/media`, {
         method: "POST",
@@ -142,13 +151,13 @@ export async function fetchAnilistMedia(mediaType: string, mediaID: number, inte
         `${inlineCode("next airing       :")} ${nextEpisode}\n`,
         `${inlineCode("chapters          :")} ${result.chapters?.toLocaleString()}\n`,
         `${inlineCode("volumes           :")} ${result.volumes?.toLocaleString()}\n`,
-        `${inlineCode("status            :")} ${capitalise(result.status)}\n`,
+        `${inlineCode("status            :")} ${displayField(result.status, "label")}\n`,
         `${inlineCode("average score     :")} ${result.averageScore}%\n`,
         `${inlineCode("mean score        :")} ${result.meanScore}%\n`,
         `${inlineCode("popularity        :")} ${result.popularity.toLocaleString()}\n`,
         `${inlineCode("favourites        :")} ${result.favourites.toLocaleString()}\n`,
-        `${inlineCode("start date        :")} ${result.startDate}\n`,
-        `${inlineCode("end date          :")} ${result.endDate}\n`,
+        `${inlineCode("start date        :")} ${displayField(result.startDate, "iso")}\n`,
+        `${inlineCode("end date          :")} ${displayField(result.endDate, "iso")}\n`,
         `${inlineCode("genres            :")} ${genresDisplay}\n\n`,
         `${inlineCode("completed         :")}\n ${userData.completed.join("")}\n`,
         `${inlineCode(`${isReading}  :`)}\n ${userData.current.join("")}\n`,
@@ -203,14 +212,6 @@ async function fetchUserData(user: number, media: number) {
     return userScore;
 }
 
-function capitalise(message: string) {
-    return message
-        .toLowerCase()
-        .split(" ")
-        .map((word) => word.charAt(0).toUpperCase() + word.slice(1))
-        .join(" ");
-}
-
 export async function fetchAnilistUserData(username: string, interaction: any): Promise<any> {
     const request = await fetch(`${env.API_URL}/user`, {
         method: "POST",